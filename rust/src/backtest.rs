@@ -0,0 +1,333 @@
+use crate::clob::{get_market, ApiCreds, ClobClient};
+use crate::config::{MarketFilter, TargetConfig};
+use crate::data_api::get_trade_activity;
+use crate::feed::{timestamp_secs, TradePayload};
+use crate::filter::TradeFilter;
+use crate::order::{paper_account_path, PaperAccount, PaperFillModel, TradeOrderBuilder};
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+const TRADES_FILE: &str = "trades.jsonl";
+const RESOLUTIONS_FILE: &str = "resolutions.json";
+
+/// Outcome of a market as far as the backtest is concerned.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarketResolution {
+    pub closed: bool,
+    /// Token ids that paid out 1 USDC per share.
+    #[serde(default)]
+    pub winners: Vec<String>,
+}
+
+impl MarketResolution {
+    pub fn is_resolved(&self) -> bool {
+        self.closed && !self.winners.is_empty()
+    }
+}
+
+/// Cached input of a backtest: the target's trades and the resolutions of the markets they touched.
+#[derive(Debug, Clone, Default)]
+pub struct BacktestDataset {
+    pub trades: Vec<TradePayload>,
+    /// condition id -> resolution
+    pub resolutions: BTreeMap<String, MarketResolution>,
+}
+
+impl BacktestDataset {
+    /// Reads `trades.jsonl` and (if present) `resolutions.json` from `dir`.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let trades = load_trades_jsonl(&dir.join(TRADES_FILE))?;
+        let resolutions = load_resolutions(&dir.join(RESOLUTIONS_FILE))?;
+        Ok(Self { trades, resolutions })
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let mut out = std::io::BufWriter::new(std::fs::File::create(dir.join(TRADES_FILE))?);
+        for trade in &self.trades {
+            writeln!(out, "{}", serde_json::to_string(trade)?)?;
+        }
+        out.flush()?;
+        std::fs::write(dir.join(RESOLUTIONS_FILE), serde_json::to_string_pretty(&self.resolutions)?)?;
+        Ok(())
+    }
+
+    /// Fetches the trades `target_wallet` made since `since_ts` from the Data API.
+    pub async fn fetch_trades(data_api_url: &str, target_wallet: &str, since_ts: u64) -> Result<Self> {
+        let trades = get_trade_activity(data_api_url, target_wallet, since_ts).await?;
+        info!("Fetched {} historical trade(s) for {}", trades.len(), target_wallet);
+        Ok(Self { trades, resolutions: BTreeMap::new() })
+    }
+
+    /// Looks up CLOB market metadata for every traded market that is not yet known to be resolved.
+    pub async fn fetch_resolutions(&mut self, clob_api_url: &str) {
+        let mut ids: Vec<String> = self.trades.iter().filter_map(|t| t.condition_id().map(str::to_string)).collect();
+        ids.sort();
+        ids.dedup();
+        for id in ids {
+            if self.resolutions.get(&id).is_some_and(|r| r.is_resolved()) {
+                continue;
+            }
+            match get_market(clob_api_url, &id).await {
+                Ok(market) => {
+                    let winners = market.tokens.iter().filter(|t| t.winner).map(|t| t.token_id.clone()).collect();
+                    self.resolutions.insert(id, MarketResolution { closed: market.closed, winners });
+                }
+                Err(e) => warn!("No market data for {}: {}", id, e),
+            }
+        }
+    }
+}
+
+/// One `TradePayload` per line, as written by [`BacktestDataset::save`] or exported from the Data API.
+pub fn load_trades_jsonl(path: &Path) -> Result<Vec<TradePayload>> {
+    let file = std::fs::File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let mut trades = Vec::new();
+    for (i, line) in std::io::BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let trade = serde_json::from_str(&line).with_context(|| format!("{}:{}", path.display(), i + 1))?;
+        trades.push(trade);
+    }
+    Ok(trades)
+}
+
+fn load_resolutions(path: &Path) -> Result<BTreeMap<String, MarketResolution>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let s = std::fs::read_to_string(path)?;
+    serde_json::from_str(&s).with_context(|| format!("parse {}", path.display()))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BacktestReport {
+    pub trades_seen: usize,
+    pub trades_copied: usize,
+    pub trades_skipped: usize,
+    /// Closed positions (sells and resolutions) with positive / non-positive PnL.
+    pub wins: usize,
+    pub losses: usize,
+    pub starting_balance: f64,
+    /// Cash plus open positions marked at the last traded price.
+    pub final_equity: f64,
+    pub realized_pnl: f64,
+    /// Largest peak-to-trough fall of equity, as a fraction of the peak.
+    pub max_drawdown: f64,
+    /// USDC bought plus USDC sold.
+    pub turnover: f64,
+    /// Positions left in markets without a known resolution, and their marked value.
+    pub open_positions: usize,
+    pub open_value: f64,
+    /// Tag or end-date filter rules were configured but not applied: they need Gamma market
+    /// metadata, which backtests do not fetch.
+    pub metadata_rules_ignored: bool,
+}
+
+impl BacktestReport {
+    pub fn hit_rate(&self) -> f64 {
+        let closed = self.wins + self.losses;
+        if closed == 0 {
+            0.0
+        } else {
+            self.wins as f64 / closed as f64
+        }
+    }
+
+    pub fn pnl(&self) -> f64 {
+        self.final_equity - self.starting_balance
+    }
+
+    pub fn roi(&self) -> f64 {
+        if self.starting_balance > 0.0 {
+            self.pnl() / self.starting_balance
+        } else {
+            0.0
+        }
+    }
+}
+
+/// The same replay with and without the order cap, to show what clipping changed.
+#[derive(Debug, Clone)]
+pub struct BacktestComparison {
+    pub capped: BacktestReport,
+    pub uncapped: BacktestReport,
+    /// Buys in the uncapped run that spent more than the cap.
+    pub clipped_orders: usize,
+}
+
+/// Replays `dataset` for `target` twice, with its order cap and without, in `work_dir`.
+pub async fn run_backtest_comparison(
+    dataset: &BacktestDataset,
+    target: &TargetConfig,
+    markets: &MarketFilter,
+    starting_balance: f64,
+    work_dir: &Path,
+) -> Result<BacktestComparison> {
+    let (capped, _) = replay(dataset, target, markets, starting_balance, &work_dir.join("capped")).await?;
+    let uncapped_target = TargetConfig {
+        max_order_amount: None,
+        ..target.clone()
+    };
+    let (uncapped, uncapped_buys) =
+        replay(dataset, &uncapped_target, markets, starting_balance, &work_dir.join("uncapped")).await?;
+    let clipped_orders = match target.max_order_amount {
+        Some(cap) => uncapped_buys.iter().flatten().filter(|spent| **spent > cap + 1e-9).count(),
+        None => 0,
+    };
+    Ok(BacktestComparison { capped, uncapped, clipped_orders })
+}
+
+/// Replays the trades through [`TradeOrderBuilder::copy_trade`] with trade-price paper fills,
+/// then settles positions of resolved markets. BUYs go through the global `markets` filter and
+/// the target's own, except for rules that need Gamma metadata. Runs entirely offline.
+pub async fn run_backtest(
+    dataset: &BacktestDataset,
+    target: &TargetConfig,
+    markets: &MarketFilter,
+    starting_balance: f64,
+    work_dir: &Path,
+) -> Result<BacktestReport> {
+    Ok(replay(dataset, target, markets, starting_balance, work_dir).await?.0)
+}
+
+/// [`run_backtest`], also returning the USDC spent per trade (`None` for anything but a copied buy).
+async fn replay(
+    dataset: &BacktestDataset,
+    target: &TargetConfig,
+    markets: &MarketFilter,
+    starting_balance: f64,
+    work_dir: &Path,
+) -> Result<(BacktestReport, Vec<Option<f64>>)> {
+    if work_dir.exists() {
+        std::fs::remove_dir_all(work_dir).with_context(|| format!("clear {}", work_dir.display()))?;
+    }
+    std::fs::create_dir_all(work_dir)?;
+    let holdings_path: PathBuf = work_dir.join("token-holding.json");
+    let account_path = paper_account_path(&holdings_path);
+    let builder = offline_order_builder(holdings_path)?.with_paper_trading(starting_balance, PaperFillModel::TradePrice);
+
+    let filter = TradeFilter::new(markets, std::slice::from_ref(target), None)?;
+
+    let mut trades: Vec<&TradePayload> = dataset
+        .trades
+        .iter()
+        .filter(|t| t.proxy_wallet.as_deref().is_none_or(|w| target.is_wallet(w)))
+        .collect();
+    trades.sort_by_key(|t| t.timestamp.map(timestamp_secs).unwrap_or(0));
+
+    let mut report = BacktestReport {
+        starting_balance,
+        metadata_rules_ignored: filter.needs_metadata(target),
+        ..Default::default()
+    };
+    let mut buys = Vec::with_capacity(trades.len());
+    let mut last_price: HashMap<String, f64> = HashMap::new();
+    let mut market_of: HashMap<String, String> = HashMap::new();
+    let mut peak = starting_balance;
+
+    for trade in trades {
+        report.trades_seen += 1;
+        last_price.insert(trade.asset.clone(), trade.price);
+        if let Some(cid) = trade.condition_id() {
+            market_of.insert(trade.asset.clone(), cid.to_string());
+        }
+        let mut spent = None;
+        let sold_fraction = builder.record_target_trade(trade, target).await;
+        if filter.check(trade, target).await.is_ok() {
+            let before = PaperAccount::load(&account_path, starting_balance);
+            let result = builder.copy_recorded_trade(trade, target, sold_fraction).await?;
+            if result.success {
+                report.trades_copied += 1;
+                let after = PaperAccount::load(&account_path, starting_balance);
+                let usdc = (after.usdc_balance - before.usdc_balance).abs();
+                report.turnover += usdc;
+                if trade.side.eq_ignore_ascii_case("BUY") {
+                    spent = Some(usdc);
+                } else {
+                    count_close(&mut report, after.realized_pnl - before.realized_pnl);
+                }
+            } else {
+                report.trades_skipped += 1;
+            }
+        } else {
+            report.trades_skipped += 1;
+        }
+        buys.push(spent);
+
+        let equity = mark_to_market(&PaperAccount::load(&account_path, starting_balance), &last_price);
+        peak = peak.max(equity);
+        if peak > 0.0 {
+            report.max_drawdown = report.max_drawdown.max((peak - equity) / peak);
+        }
+    }
+
+    let mut account = PaperAccount::load(&account_path, starting_balance);
+    let tokens: Vec<String> = account.positions.keys().cloned().collect();
+    for token in tokens {
+        let resolution = market_of.get(&token).and_then(|cid| dataset.resolutions.get(cid));
+        if let Some(r) = resolution.filter(|r| r.is_resolved()) {
+            let payout = if r.winners.contains(&token) { 1.0 } else { 0.0 };
+            if let Some(pnl) = account.settle(&token, payout) {
+                count_close(&mut report, pnl);
+            }
+        }
+    }
+    account.save(&account_path)?;
+
+    report.final_equity = mark_to_market(&account, &last_price);
+    peak = peak.max(report.final_equity);
+    if peak > 0.0 {
+        report.max_drawdown = report.max_drawdown.max((peak - report.final_equity) / peak);
+    }
+    report.realized_pnl = account.realized_pnl;
+    report.open_positions = account.positions.len();
+    report.open_value = report.final_equity - account.usdc_balance;
+    Ok((report, buys))
+}
+
+fn count_close(report: &mut BacktestReport, pnl: f64) {
+    if pnl > 0.0 {
+        report.wins += 1;
+    } else {
+        report.losses += 1;
+    }
+}
+
+fn mark_to_market(account: &PaperAccount, last_price: &HashMap<String, f64>) -> f64 {
+    account.usdc_balance
+        + account
+            .positions
+            .iter()
+            .map(|(token, p)| p.shares * last_price.get(token).copied().unwrap_or(0.0))
+            .sum::<f64>()
+}
+
+/// An order builder that never touches the network: throwaway wallet, no credentials.
+fn offline_order_builder(holdings_path: PathBuf) -> Result<TradeOrderBuilder> {
+    let creds = ApiCreds {
+        api_key: String::new(),
+        secret: String::new(),
+        passphrase: String::new(),
+    };
+    let wallet = LocalWallet::new(&mut ethers::core::rand::thread_rng());
+    let clob = ClobClient::new(String::new(), creds, format!("{:?}", wallet.address()), 0);
+    let provider = Provider::<Http>::try_from("http://127.0.0.1:8545")?;
+    Ok(TradeOrderBuilder::new(
+        clob,
+        provider,
+        wallet,
+        137,
+        holdings_path,
+        "0.01".to_string(),
+        false,
+        "FAK".to_string(),
+    ))
+}
@@ -1,29 +1,68 @@
-use crate::clob::ClobClient;
+use crate::clob::{ClobClient, OpenOrder};
 use anyhow::Result;
+use std::collections::HashMap;
 use tracing::{info, warn};
 
 const COLLATERAL: &str = "COLLATERAL";
+/// USDC and outcome tokens have 6 decimals; `/balance-allowance` reports base units.
+const TOKEN_DECIMALS: f64 = 1e6;
 
-pub async fn get_available_balance(client: &ClobClient, token_id: Option<&str>) -> Result<f64> {
-    let balance_resp = client.get_balance_allowance(COLLATERAL).await?;
-    let total: f64 = balance_resp.balance.as_deref().unwrap_or("0").parse().unwrap_or(0.0);
-    let orders = client.get_open_orders(token_id).await?;
-    let mut reserved = 0.0;
-    for order in orders {
-        let side = order.side.as_deref().unwrap_or("").to_uppercase();
-        if side == "BUY" {
-            let orig: f64 = order.original_size.as_deref().unwrap_or("0").parse().unwrap_or(0.0);
-            let matched: f64 = order.size_matched.as_deref().unwrap_or("0").parse().unwrap_or(0.0);
-            reserved += orig - matched;
+/// A base-unit amount from the CLOB (`"1500000"`) in whole units (1.5); 0 if missing or
+/// unparseable.
+pub fn from_base_units(amount: Option<&str>) -> f64 {
+    amount.and_then(|a| a.trim().parse::<f64>().ok()).unwrap_or(0.0) / TOKEN_DECIMALS
+}
+
+/// What our open orders lock up.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Reserved {
+    /// USDC held for open BUYs: remaining shares × limit price.
+    pub collateral: f64,
+    /// Shares held for open SELLs, by token id.
+    pub shares: HashMap<String, f64>,
+}
+
+impl Reserved {
+    pub fn from_orders(orders: &[OpenOrder]) -> Self {
+        let mut reserved = Reserved::default();
+        for order in orders.iter().filter(|o| o.is_live()) {
+            let parse = |v: &Option<String>| v.as_deref().and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
+            let remaining = (parse(&order.original_size) - parse(&order.size_matched)).max(0.0);
+            match order.side.as_deref().unwrap_or("").to_uppercase().as_str() {
+                "BUY" => reserved.collateral += remaining * parse(&order.price),
+                "SELL" => {
+                    let token = order.asset_id.clone().unwrap_or_default();
+                    *reserved.shares.entry(token).or_default() += remaining;
+                }
+                _ => {}
+            }
         }
+        reserved
+    }
+
+    /// Shares of `token_id` already offered by open SELLs.
+    pub fn shares_of(&self, token_id: &str) -> f64 {
+        self.shares.get(token_id).copied().unwrap_or(0.0)
     }
-    Ok((total - reserved).max(0.0))
+}
+
+/// Collateral and shares reserved by our open orders, optionally only those for `token_id`.
+pub async fn get_reserved(client: &ClobClient, token_id: Option<&str>) -> Result<Reserved> {
+    Ok(Reserved::from_orders(&client.get_open_orders(token_id).await?))
+}
+
+/// USDC balance minus what open BUYs (optionally only those for `token_id`) have reserved.
+pub async fn get_available_balance(client: &ClobClient, token_id: Option<&str>) -> Result<f64> {
+    let balance_resp = client.get_balance_allowance(COLLATERAL).await?;
+    let total = from_base_units(balance_resp.balance.as_deref());
+    let reserved = get_reserved(client, token_id).await?;
+    Ok((total - reserved.collateral).max(0.0))
 }
 
 pub async fn display_wallet_balance(client: &ClobClient) -> Result<()> {
     let r = client.get_balance_allowance(COLLATERAL).await?;
-    let balance: f64 = r.balance.as_deref().unwrap_or("0").parse().unwrap_or(0.0);
-    let allowance: f64 = r.allowance.as_deref().unwrap_or("0").parse().unwrap_or(0.0);
+    let balance = from_base_units(r.balance.as_deref());
+    let allowance = from_base_units(r.allowance.as_deref());
     info!("═══════════════════════════════════════");
     info!("💰 WALLET BALANCE & ALLOWANCE");
     info!("═══════════════════════════════════════");
@@ -35,9 +74,9 @@ pub async fn display_wallet_balance(client: &ClobClient) -> Result<()> {
 
 pub async fn validate_buy_order_balance(client: &ClobClient, required_amount: f64) -> Result<BalanceCheck> {
     let r = client.get_balance_allowance(COLLATERAL).await?;
-    let balance: f64 = r.balance.as_deref().unwrap_or("0").parse().unwrap_or(0.0);
-    let allowance: f64 = r.allowance.as_deref().unwrap_or("0").parse().unwrap_or(0.0);
-    let available = get_available_balance(client, None).await?;
+    let balance = from_base_units(r.balance.as_deref());
+    let allowance = from_base_units(r.allowance.as_deref());
+    let available = (balance - get_reserved(client, None).await?.collateral).max(0.0);
     let valid = available >= required_amount;
     if !valid {
         warn!("Insufficient balance: required={:.6} available={:.6}", required_amount, available);
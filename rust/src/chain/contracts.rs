@@ -6,6 +6,7 @@ use std::sync::Arc;
 use tracing::info;
 
 const USDC_APPROVE_SELECTOR: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3]; // approve(address,uint256)
+const BALANCE_OF_SELECTOR: [u8; 4] = [0x70, 0xa0, 0x82, 0x31]; // balanceOf(address)
 const CTF_SET_APPROVAL_SELECTOR: [u8; 4] = [0xa2, 0x2c, 0x46, 0x0d]; // setApprovalForAll(address,bool)
 
 fn max_uint256() -> U256 {
@@ -124,7 +125,26 @@ pub async fn approve_tokens_after_buy(
     Ok(())
 }
 
-fn address_from_hex(s: &str) -> Result<Address> {
+/// Ensures the NegRiskAdapter may move our CTF positions, which its `redeemPositions` requires.
+pub async fn approve_neg_risk_adapter(provider: &Provider<Http>, wallet: &LocalWallet, chain_id: u64) -> Result<()> {
+    let client = SignerMiddleware::new(provider.clone(), wallet.clone());
+    let client = Arc::new(client);
+    let cfg = get_contract_config(chain_id);
+    let ctf = address_from_hex(&cfg.conditional_tokens)?;
+    let neg_adapter = address_from_hex(&cfg.neg_risk_adapter)?;
+
+    if !call_is_approved_for_all(&client, ctf, wallet.address(), neg_adapter).await? {
+        let gas_price = provider.get_gas_price().await.unwrap_or(U256::from(100_000_000_000u64));
+        let gas_options = GasOpts::default()
+            .with_gas_price(gas_price * 120 / 100)
+            .with_gas(200_000u64);
+        call_set_approval_for_all(&client, ctf, neg_adapter, true, &gas_options).await?;
+        info!("ConditionalTokens approved for NegRiskAdapter (redeem)");
+    }
+    Ok(())
+}
+
+pub(crate) fn address_from_hex(s: &str) -> Result<Address> {
     let s = s.trim_start_matches("0x");
     let bytes = hex::decode(s).context("Invalid address hex")?;
     if bytes.len() != 20 {
@@ -135,6 +155,17 @@ fn address_from_hex(s: &str) -> Result<Address> {
     Ok(Address::from(arr))
 }
 
+/// USDC held by `owner` on chain, in whole units.
+pub async fn usdc_balance(provider: &Provider<Http>, chain_id: u64, owner: Address) -> Result<f64> {
+    let usdc = address_from_hex(&get_contract_config(chain_id).collateral)?;
+    let mut data = Vec::from(BALANCE_OF_SELECTOR);
+    data.extend_from_slice(&ethers::abi::encode(&[ethers::abi::Token::Address(owner)]));
+    let tx = TransactionRequest::default().to(usdc).data(Bytes::from(data));
+    let res = provider.call(&tx.into(), None).await.context("balanceOf call")?;
+    let out: [u8; 32] = res.as_ref().try_into().context("balanceOf result length")?;
+    Ok(U256::from_big_endian(&out).as_u128() as f64 / 1e6)
+}
+
 async fn call_allowance(
     client: &Arc<SignerMiddleware<Provider<Http>, LocalWallet>>,
     token: Address,
@@ -219,17 +250,12 @@ async fn call_set_approval_for_all(
     Ok(())
 }
 
+#[derive(Default)]
 struct GasOpts {
     gas: Option<u64>,
     gas_price: Option<U256>,
 }
 
-impl Default for GasOpts {
-    fn default() -> Self {
-        Self { gas: None, gas_price: None }
-    }
-}
-
 impl GasOpts {
     fn with_gas(mut self, g: u64) -> Self {
         self.gas = Some(g);
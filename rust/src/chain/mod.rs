@@ -1,6 +1,7 @@
 mod contracts;
 
-pub use contracts::{approve_tokens_after_buy, approve_usdc_allowance};
+pub use contracts::{approve_neg_risk_adapter, approve_tokens_after_buy, approve_usdc_allowance, usdc_balance};
+pub(crate) use contracts::address_from_hex;
 
 
 #[derive(Debug, Clone)]
@@ -1,14 +1,112 @@
-use crate::clob::ApiCreds;
+use crate::clob::retry::parse_retry_after;
+use crate::clob::{ApiCreds, ClobError, RetryPolicy};
 use anyhow::{Context, Result};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use ethers::signers::Signer;
 use hmac::{Hmac, Mac};
+use reqwest::{Method, RequestBuilder, StatusCode};
 use sha2::Sha256;
-use std::time::{SystemTime, UNIX_EPOCH};
-use tracing::info;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Time allowed to connect to the CLOB.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Time allowed for a whole request, reading the response included.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How long a token's tick size is reused; it changes when the price nears 0 or 1.
+const TICK_SIZE_TTL: Duration = Duration::from_secs(60);
+/// How long a token's neg-risk flag is reused; it is fixed per market.
+const NEG_RISK_TTL: Duration = Duration::from_secs(3600);
+
+/// `next_cursor` of the first page, and the one the CLOB returns after the last page.
+const FIRST_CURSOR: &str = "MA==";
+const END_CURSOR: &str = "LTE=";
+
+/// Per-token values cached with the time they were fetched.
+type TtlCache<T> = Arc<Mutex<HashMap<String, (Instant, T)>>>;
+
+fn cached<T: Clone>(cache: &TtlCache<T>, key: &str, ttl: Duration) -> Option<T> {
+    match cache.lock().unwrap().get(key) {
+        Some((fetched, value)) if fetched.elapsed() < ttl => Some(value.clone()),
+        _ => None,
+    }
+}
+
+/// The pooled HTTP client all CLOB, Data API and Gamma requests share.
+pub(crate) fn http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("build HTTP client")
+    })
+}
+
+/// A successful (2xx) response.
+struct Response {
+    op: &'static str,
+    status: StatusCode,
+    body: String,
+}
+
+impl Response {
+    fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, ClobError> {
+        serde_json::from_str(&self.body).map_err(|e| ClobError::Decode {
+            op: self.op,
+            status: self.status,
+            body: self.body.clone(),
+            message: e.to_string(),
+        })
+    }
+}
+
+/// Sends `req`, failing with a `ClobError` unless the CLOB answers 2xx.
+async fn send(op: &'static str, req: RequestBuilder) -> Result<Response, ClobError> {
+    let res = req.send().await.map_err(|source| ClobError::Request { op, source })?;
+    let status = res.status();
+    let retry_after = res
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_retry_after);
+    let body = res.text().await.map_err(|source| ClobError::Request { op, source })?;
+    if !status.is_success() {
+        return Err(ClobError::from_status(op, status, body, retry_after));
+    }
+    Ok(Response { op, status, body })
+}
+
+/// Sends the request `build` makes, retrying retryable failures under `policy`. `build` runs
+/// for every attempt so that signed requests get a fresh timestamp. Only for requests that
+/// are safe to repeat.
+async fn send_with_retry(
+    op: &'static str,
+    policy: &RetryPolicy,
+    build: impl Fn() -> Result<RequestBuilder>,
+) -> Result<Response> {
+    let mut attempt = 0;
+    loop {
+        let err = match send(op, build()?).await {
+            Ok(res) => return Ok(res),
+            Err(e) if e.is_retryable() => e,
+            Err(e) => return Err(e.into()),
+        };
+        let Some(delay) = policy.delay(attempt, err.retry_after()) else {
+            return Err(err.into());
+        };
+        warn!("{}; retrying in {:?}", err, delay);
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
 pub fn wallet_address(private_key: &str) -> Result<String> {
     let key = private_key.trim_start_matches("0x");
     let bytes = hex::decode(key).context("invalid private key hex")?;
@@ -78,96 +176,422 @@ pub fn build_l2_signature(secret_b64: &str, timestamp: u64, method: &str, path:
     Ok(url_safe)
 }
 
+/// Public market metadata from `GET /markets/{condition_id}` (no auth required).
+pub async fn get_market(base_url: &str, condition_id: &str) -> Result<ClobMarket> {
+    let url = format!("{}/markets/{}", base_url.trim_end_matches('/'), condition_id);
+    let out: ClobMarket = send_with_retry("get market", &RetryPolicy::default(), || Ok(http_client().get(&url))).await?.json()?;
+    Ok(out)
+}
+
+/// Public order book from `GET /book?token_id=` (no auth required).
+pub async fn get_order_book(base_url: &str, token_id: &str) -> Result<OrderBook> {
+    let url = format!("{}/book?token_id={}", base_url.trim_end_matches('/'), token_id);
+    let out: OrderBook = send_with_retry("get order book", &RetryPolicy::default(), || Ok(http_client().get(&url))).await?.json()?;
+    Ok(out)
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MidpointResponse {
+    mid: String,
+}
+
+/// Midpoint of the best bid and ask from `GET /midpoint?token_id=` (no auth required).
+pub async fn get_midpoint(base_url: &str, token_id: &str) -> Result<f64> {
+    let url = format!("{}/midpoint?token_id={}", base_url.trim_end_matches('/'), token_id);
+    let out: MidpointResponse = send_with_retry("get midpoint", &RetryPolicy::default(), || Ok(http_client().get(&url))).await?.json()?;
+    out.mid.parse().with_context(|| format!("invalid midpoint {:?}", out.mid))
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct TickSizeResponse {
+    minimum_tick_size: f64,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct NegRiskResponse {
+    neg_risk: bool,
+}
+
+/// Clones share the tick size and neg-risk caches.
 #[derive(Clone)]
 pub struct ClobClient {
     pub base_url: String,
     pub creds: ApiCreds,
+    /// Address of the signing key (the `POLY_ADDRESS` of L2 auth).
     pub wallet_address: String,
     pub signature_type: u8,
+    /// Proxy wallet that makes our orders, for signature types 1 and 2.
+    pub funder_address: Option<String>,
+    retry: RetryPolicy,
+    tick_sizes: TtlCache<String>,
+    neg_risks: TtlCache<bool>,
 }
 
 impl ClobClient {
     pub fn new(base_url: String, creds: ApiCreds, wallet_address: String, signature_type: u8) -> Self {
-        Self { base_url, creds, wallet_address, signature_type }
+        Self {
+            base_url,
+            creds,
+            wallet_address,
+            signature_type,
+            funder_address: None,
+            retry: RetryPolicy::default(),
+            tick_sizes: TtlCache::default(),
+            neg_risks: TtlCache::default(),
+        }
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Orders are made by `funder_address` (a Polymarket proxy or Safe) and signed by our key.
+    pub fn with_funder(mut self, funder_address: Option<String>) -> Self {
+        self.funder_address = funder_address;
+        self
+    }
+
+    /// The order maker: the funder if one is set, else the signing wallet.
+    pub fn maker_address(&self) -> &str {
+        self.funder_address.as_deref().unwrap_or(&self.wallet_address)
+    }
+
+    /// Minimum tick size of `token_id` from `GET /tick-size` (e.g. `"0.01"`), cached briefly.
+    pub async fn get_tick_size(&self, token_id: &str) -> Result<String> {
+        if let Some(tick_size) = cached(&self.tick_sizes, token_id, TICK_SIZE_TTL) {
+            return Ok(tick_size);
+        }
+        let url = format!("{}/tick-size?token_id={}", self.base_url.trim_end_matches('/'), token_id);
+        let out: TickSizeResponse = send_with_retry("get tick size", &self.retry, || Ok(http_client().get(&url))).await?.json()?;
+        let tick_size = out.minimum_tick_size.to_string();
+        if crate::config::TickSize::parse(&tick_size).is_none() {
+            anyhow::bail!("unsupported tick size {}", tick_size);
+        }
+        self.tick_sizes
+            .lock()
+            .unwrap()
+            .insert(token_id.to_string(), (Instant::now(), tick_size.clone()));
+        Ok(tick_size)
+    }
+
+    /// Whether `token_id` trades on the neg-risk exchange, from `GET /neg-risk`; cached.
+    pub async fn get_neg_risk(&self, token_id: &str) -> Result<bool> {
+        if let Some(neg_risk) = cached(&self.neg_risks, token_id, NEG_RISK_TTL) {
+            return Ok(neg_risk);
+        }
+        let url = format!("{}/neg-risk?token_id={}", self.base_url.trim_end_matches('/'), token_id);
+        let out: NegRiskResponse = send_with_retry("get neg risk", &self.retry, || Ok(http_client().get(&url))).await?.json()?;
+        self.neg_risks
+            .lock()
+            .unwrap()
+            .insert(token_id.to_string(), (Instant::now(), out.neg_risk));
+        Ok(out.neg_risk)
     }
 
     pub async fn get_balance_allowance(&self, asset_type: &str) -> Result<BalanceAllowanceResponse> {
-        let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
         let path = format!("/balance-allowance?asset_type={}&signature_type={}", asset_type, self.signature_type);
-        let sig = build_l2_signature(&self.creds.secret, ts, "GET", &path, None)?;
-        let url = format!("{}{}", self.base_url.trim_end_matches('/'), path);
-        let client = reqwest::Client::new();
-        let res = client
-            .get(&url)
-            .header("POLY_ADDRESS", &self.wallet_address)
-            .header("POLY_SIGNATURE", sig)
-            .header("POLY_TIMESTAMP", ts.to_string())
-            .header("POLY_API_KEY", &self.creds.api_key)
-            .header("POLY_PASSPHRASE", &self.creds.passphrase)
-            .send()
-            .await?;
-        let out: BalanceAllowanceResponse = res.json().await?;
+        let out: BalanceAllowanceResponse = self.request("get balance allowance", Method::GET, &path, None).await?.json()?;
         Ok(out)
     }
 
     pub async fn update_balance_allowance(&self, asset_type: &str) -> Result<()> {
-        let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-        let path = "/balance-allowance/update";
         let body = format!(r#"{{"asset_type":"{}"}}"#, asset_type);
-        let sig = build_l2_signature(&self.creds.secret, ts, "POST", path, Some(&body))?;
-        let url = format!("{}{}", self.base_url.trim_end_matches('/'), path);
-        let client = reqwest::Client::new();
-        let res = client
-            .post(&url)
-            .header("POLY_ADDRESS", &self.wallet_address)
-            .header("POLY_SIGNATURE", sig)
-            .header("POLY_TIMESTAMP", ts.to_string())
-            .header("POLY_API_KEY", &self.creds.api_key)
-            .header("POLY_PASSPHRASE", &self.creds.passphrase)
-            .body(body)
-            .send()
+        self.request("update balance allowance", Method::POST, "/balance-allowance/update", Some(body))
             .await?;
-        if !res.status().is_success() {
-            let t = res.text().await.unwrap_or_default();
-            anyhow::bail!("update balance allowance failed: {}", t);
-        }
         info!("CLOB balance allowance updated");
         Ok(())
     }
 
+    /// All our open orders, optionally only those for `asset_id`, following `next_cursor`
+    /// through every page of `GET /data/orders`. An empty list means there are none; a failed
+    /// request is an error.
     pub async fn get_open_orders(&self, asset_id: Option<&str>) -> Result<Vec<OpenOrder>> {
-        let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
-        let path = if let Some(id) = asset_id {
-            format!("/data/orders?asset_id={}", id)
-        } else {
-            "/data/orders".to_string()
+        let mut orders = Vec::new();
+        let mut cursor = FIRST_CURSOR.to_string();
+        loop {
+            let path = match asset_id {
+                Some(id) => format!("/data/orders?asset_id={}&next_cursor={}", id, cursor),
+                None => format!("/data/orders?next_cursor={}", cursor),
+            };
+            let out: OrdersResponse = self.request("get open orders", Method::GET, &path, None).await?.json()?;
+            let next = match out {
+                OrdersResponse::Page { data, next_cursor } => {
+                    orders.extend(data);
+                    next_cursor
+                }
+                OrdersResponse::List(page) => {
+                    orders.extend(page);
+                    None
+                }
+            };
+            match next {
+                Some(next) if !next.is_empty() && next != END_CURSOR => {
+                    if next == cursor {
+                        anyhow::bail!("get open orders failed: cursor {} repeated", next);
+                    }
+                    cursor = next;
+                }
+                _ => return Ok(orders),
+            }
+        }
+    }
+
+    /// Our trades in `asset_id` matched after `after` (unix seconds), from the first page of
+    /// `GET /data/trades`.
+    pub async fn get_trades(&self, asset_id: &str, after: u64) -> Result<Vec<ClobTrade>> {
+        let path = format!(
+            "/data/trades?maker_address={}&asset_id={}&after={}",
+            self.maker_address(), asset_id, after
+        );
+        let out: TradesResponse = self.request("get trades", Method::GET, &path, None).await?.json()?;
+        Ok(match out {
+            TradesResponse::Page { data } => data,
+            TradesResponse::List(trades) => trades,
+        })
+    }
+
+    /// An order of ours by id from `GET /data/order/{id}`, whatever its status; `None` if the
+    /// CLOB does not know it (empty, `null` or 404).
+    pub async fn get_order(&self, order_id: &str) -> Result<Option<OpenOrder>> {
+        let path = format!("/data/order/{}", order_id);
+        let res = match self.request("get order", Method::GET, &path, None).await {
+            Ok(res) => res,
+            Err(e) if matches!(e.downcast_ref(), Some(ClobError::Validation { status, .. }) if *status == StatusCode::NOT_FOUND) => {
+                return Ok(None)
+            }
+            Err(e) => return Err(e),
         };
-        let sig = build_l2_signature(&self.creds.secret, ts, "GET", &path, None)?;
+        if res.body.trim().is_empty() {
+            return Ok(None);
+        }
+        let out: Option<OpenOrder> = res.json()?;
+        Ok(out)
+    }
+
+    /// Cancels one order with `DELETE /order`; fails with the CLOB's reason if it was not
+    /// cancelled (e.g. already filled).
+    pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
+        let body = serde_json::json!({ "orderID": order_id }).to_string();
+        let out = self.delete("/order", Some(body)).await?;
+        if let Some(reason) = out.not_canceled.get(order_id) {
+            anyhow::bail!("order {} not cancelled: {}", order_id, reason);
+        }
+        Ok(())
+    }
+
+    /// Cancels several orders in one `DELETE /orders`.
+    pub async fn cancel_orders(&self, order_ids: &[String]) -> Result<CancelOrdersResponse> {
+        self.delete("/orders", Some(serde_json::to_string(order_ids)?)).await
+    }
+
+    /// Cancels our orders in a market (condition id), optionally only those for one token,
+    /// with `DELETE /cancel-market-orders`.
+    pub async fn cancel_market_orders(&self, market: &str, asset_id: Option<&str>) -> Result<CancelOrdersResponse> {
+        let body = serde_json::json!({ "market": market, "asset_id": asset_id.unwrap_or("") }).to_string();
+        self.delete("/cancel-market-orders", Some(body)).await
+    }
+
+    /// Cancels every open order of this API key with `DELETE /cancel-all`.
+    pub async fn cancel_all(&self) -> Result<CancelOrdersResponse> {
+        self.delete("/cancel-all", None).await
+    }
+
+    async fn delete(&self, path: &str, body: Option<String>) -> Result<CancelOrdersResponse> {
+        let out: CancelOrdersResponse = self.request("cancel", Method::DELETE, path, body).await?.json()?;
+        Ok(out)
+    }
+
+    /// Posts a signed order. `order_id` is its order hash, which the CLOB uses as the order id:
+    /// after a retryable failure the order is looked up first and only posted again if the
+    /// CLOB does not have it.
+    pub async fn post_order(&self, order: &serde_json::Value, order_id: &str) -> Result<PostOrderResponse> {
+        let body = serde_json::to_string(order)?;
+        let mut attempt = 0;
+        loop {
+            let err = match send("post order", self.l2_request(Method::POST, "/order", Some(body.clone()))?).await {
+                Ok(res) => return Ok(res.json()?),
+                Err(e) if e.is_retryable() => e,
+                Err(e) => return Err(e.into()),
+            };
+            let Some(delay) = self.retry.delay(attempt, err.retry_after()) else {
+                return Err(err.into());
+            };
+            match self.get_order(order_id).await {
+                Ok(Some(found)) => {
+                    warn!("{}; order {} was placed anyway", err, order_id);
+                    return Ok(PostOrderResponse {
+                        success: true,
+                        order_id: Some(order_id.to_string()),
+                        status: found.status.map(|s| s.to_lowercase()),
+                        ..Default::default()
+                    });
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    warn!("Could not check whether order {} was placed: {}", order_id, e);
+                    return Err(err.into());
+                }
+            }
+            warn!("{}; order {} not placed, retrying in {:?}", err, order_id, delay);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Sends an L2-signed request, re-signed for every retry. Not for order posts.
+    async fn request(&self, op: &'static str, method: Method, path: &str, body: Option<String>) -> Result<Response> {
+        send_with_retry(op, &self.retry, || self.l2_request(method.clone(), path, body.clone())).await
+    }
+
+    /// A request to `path` signed with the L2 headers for the current time; a JSON `body` is
+    /// part of the signature.
+    fn l2_request(&self, method: Method, path: &str, body: Option<String>) -> Result<RequestBuilder> {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let sig = build_l2_signature(&self.creds.secret, ts, method.as_str(), path, body.as_deref())?;
         let url = format!("{}{}", self.base_url.trim_end_matches('/'), path);
-        let client = reqwest::Client::new();
-        let res = client
-            .get(&url)
+        let mut req = http_client()
+            .request(method, &url)
             .header("POLY_ADDRESS", &self.wallet_address)
             .header("POLY_SIGNATURE", sig)
             .header("POLY_TIMESTAMP", ts.to_string())
             .header("POLY_API_KEY", &self.creds.api_key)
-            .header("POLY_PASSPHRASE", &self.creds.passphrase)
-            .send()
-            .await?;
-        let out: Vec<OpenOrder> = res.json().await.unwrap_or_default();
-        Ok(out)
+            .header("POLY_PASSPHRASE", &self.creds.passphrase);
+        if let Some(body) = body {
+            req = req.header("Content-Type", "application/json").body(body);
+        }
+        Ok(req)
     }
 }
 
-#[derive(serde::Deserialize)]
+#[derive(Debug, serde::Deserialize)]
 pub struct BalanceAllowanceResponse {
     pub balance: Option<String>,
     pub allowance: Option<String>,
 }
 
-#[derive(serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 pub struct OpenOrder {
+    pub id: Option<String>,
+    /// `LIVE` while resting; `MATCHED`, `CANCELED` etc. once done.
+    pub status: Option<String>,
+    /// Condition id.
+    pub market: Option<String>,
+    pub asset_id: Option<String>,
     pub side: Option<String>,
     pub original_size: Option<String>,
     pub size_matched: Option<String>,
+    pub price: Option<String>,
+    /// `GTC`, `GTD`, ...
+    pub order_type: Option<String>,
+    /// Unix seconds.
+    pub created_at: Option<i64>,
+}
+
+impl OpenOrder {
+    /// Whether the order can still fill.
+    pub fn is_live(&self) -> bool {
+        self.status.as_deref().is_none_or(|s| {
+            let s = s.to_uppercase();
+            let s = s.trim_start_matches("ORDER_STATUS_");
+            s == "LIVE" || s == "DELAYED"
+        })
+    }
+}
+
+/// A match one of our orders took part in, as taker or as one of the makers.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ClobTrade {
+    pub id: Option<String>,
+    pub taker_order_id: Option<String>,
+    pub side: Option<String>,
+    /// Shares the taker order matched.
+    pub size: String,
+    /// Taker's price.
+    pub price: String,
+    pub status: Option<String>,
+    #[serde(default)]
+    pub maker_orders: Vec<MakerOrder>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct MakerOrder {
+    pub order_id: String,
+    pub matched_amount: String,
+    pub price: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum OrdersResponse {
+    Page { data: Vec<OpenOrder>, next_cursor: Option<String> },
+    List(Vec<OpenOrder>),
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum TradesResponse {
+    Page { data: Vec<ClobTrade> },
+    List(Vec<ClobTrade>),
+}
+
+/// Result of the cancel endpoints: ids cancelled, and ids that were not with the reason.
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct CancelOrdersResponse {
+    #[serde(default)]
+    pub canceled: Vec<String>,
+    #[serde(default)]
+    pub not_canceled: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct PostOrderResponse {
+    #[serde(default)]
+    pub success: bool,
+    #[serde(rename = "errorMsg")]
+    pub error_msg: Option<String>,
+    #[serde(rename = "orderID")]
+    pub order_id: Option<String>,
+    #[serde(rename = "transactionsHashes")]
+    pub transactions_hashes: Option<Vec<String>>,
+    pub status: Option<String>,
+    #[serde(rename = "makingAmount")]
+    pub making_amount: Option<String>,
+    #[serde(rename = "takingAmount")]
+    pub taking_amount: Option<String>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ClobMarket {
+    pub condition_id: Option<String>,
+    #[serde(default)]
+    pub neg_risk: bool,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub tokens: Vec<ClobMarketToken>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ClobMarketToken {
+    pub token_id: String,
+    pub outcome: Option<String>,
+    #[serde(default)]
+    pub winner: bool,
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct OrderBook {
+    pub market: Option<String>,
+    pub asset_id: Option<String>,
+    #[serde(default)]
+    pub bids: Vec<BookLevel>,
+    #[serde(default)]
+    pub asks: Vec<BookLevel>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct BookLevel {
+    pub price: String,
+    pub size: String,
 }
@@ -29,8 +29,7 @@ pub async fn create_or_load_credential(
     let wallet_addr = crate::clob::client::wallet_address(private_key)?;
 
     let url = format!("{}/auth/derive-api-key?timestamp={}&nonce=0", clob_base_url.trim_end_matches('/'), ts);
-    let client = reqwest::Client::new();
-    let res = client
+    let res = crate::clob::client::http_client()
         .get(&url)
         .header("POLY_ADDRESS", format!("{:?}", wallet_addr))
         .header("POLY_SIGNATURE", sig)
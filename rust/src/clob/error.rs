@@ -0,0 +1,84 @@
+use reqwest::StatusCode;
+use std::time::Duration;
+
+/// Why a CLOB request failed. `ClobClient` methods return it inside `anyhow::Error`; use
+/// `err.downcast_ref::<ClobError>()` to tell the cases apart.
+#[derive(Debug, thiserror::Error)]
+pub enum ClobError {
+    /// 401/403: missing, invalid or revoked API credentials.
+    #[error("{op} failed: unauthorized: {status} {body}")]
+    Auth { op: &'static str, status: StatusCode, body: String },
+    /// 429: too many requests.
+    #[error("{op} failed: rate limited: {status} {body}")]
+    RateLimited { op: &'static str, status: StatusCode, body: String, retry_after: Option<Duration> },
+    /// Any other 4xx: the CLOB rejected the request itself.
+    #[error("{op} failed: rejected: {status} {body}")]
+    Validation { op: &'static str, status: StatusCode, body: String },
+    /// 5xx or another unexpected status.
+    #[error("{op} failed: server error: {status} {body}")]
+    Server { op: &'static str, status: StatusCode, body: String, retry_after: Option<Duration> },
+    /// The response was a success but not the JSON we expected.
+    #[error("{op} failed: could not parse response ({status}): {message}: {body}")]
+    Decode { op: &'static str, status: StatusCode, body: String, message: String },
+    /// No response: connection failure or timeout.
+    #[error("{op} failed: {source}")]
+    Request {
+        op: &'static str,
+        #[source]
+        source: reqwest::Error,
+    },
+}
+
+impl ClobError {
+    /// Classifies a non-2xx response; `retry_after` is its `Retry-After` header.
+    pub fn from_status(op: &'static str, status: StatusCode, body: String, retry_after: Option<Duration>) -> Self {
+        match status.as_u16() {
+            401 | 403 => ClobError::Auth { op, status, body },
+            429 => ClobError::RateLimited { op, status, body, retry_after },
+            400..=499 => ClobError::Validation { op, status, body },
+            _ => ClobError::Server { op, status, body, retry_after },
+        }
+    }
+
+    /// Whether the same request may succeed later: rate limiting, 5xx, or no response.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ClobError::RateLimited { .. } => true,
+            ClobError::Server { status, .. } => status.is_server_error(),
+            ClobError::Request { source, .. } => !source.is_builder(),
+            _ => false,
+        }
+    }
+
+    /// How long the CLOB asked us to wait before retrying.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ClobError::RateLimited { retry_after, .. } | ClobError::Server { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the response, if there was one.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            ClobError::Auth { status, .. }
+            | ClobError::RateLimited { status, .. }
+            | ClobError::Validation { status, .. }
+            | ClobError::Server { status, .. }
+            | ClobError::Decode { status, .. } => Some(*status),
+            ClobError::Request { source, .. } => source.status(),
+        }
+    }
+
+    /// Response body, if there was one.
+    pub fn body(&self) -> Option<&str> {
+        match self {
+            ClobError::Auth { body, .. }
+            | ClobError::RateLimited { body, .. }
+            | ClobError::Validation { body, .. }
+            | ClobError::Server { body, .. }
+            | ClobError::Decode { body, .. } => Some(body),
+            ClobError::Request { .. } => None,
+        }
+    }
+}
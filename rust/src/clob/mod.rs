@@ -1,5 +1,12 @@
 pub mod client;
 pub mod credential;
+pub mod error;
+pub mod retry;
 
-pub use client::{wallet_address, ClobClient};
+pub use client::{
+    get_market, get_midpoint, get_order_book, wallet_address, BookLevel, CancelOrdersResponse, ClobClient, ClobMarket,
+    ClobTrade, MakerOrder, OpenOrder, OrderBook, PostOrderResponse,
+};
 pub use credential::{create_or_load_credential, ApiCreds};
+pub use error::ClobError;
+pub use retry::RetryPolicy;
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+/// How `ClobClient` retries requests that failed with a 429, a 5xx or no response.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first included; 1 disables retries.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    /// Longest wait between attempts. A `Retry-After` beyond it ends the retries instead.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn none() -> Self {
+        Self { max_attempts: 1, ..Self::default() }
+    }
+
+    /// Wait before retry `attempt` (0 for the first retry): `retry_after` when the CLOB sent
+    /// one, else exponential backoff with "equal jitter", a random delay in `[d/2, d]` for
+    /// `d = initial * 2^attempt`. `None` once the attempts are used up or the CLOB asks for
+    /// a longer wait than `max_backoff`.
+    pub fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt + 1 >= self.max_attempts {
+            return None;
+        }
+        if let Some(wait) = retry_after {
+            return (wait <= self.max_backoff).then_some(wait);
+        }
+        let base = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.min(16)))
+            .min(self.max_backoff);
+        let jitter: f64 = ethers::core::rand::random();
+        Some(base / 2 + base.mul_f64(jitter / 2.0))
+    }
+}
+
+/// Parses a `Retry-After` header: delay seconds or an HTTP date.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let secs = (at.timestamp() - chrono::Utc::now().timestamp()).max(0);
+    Some(Duration::from_secs(secs as u64))
+}
@@ -0,0 +1,158 @@
+use super::{MarketFilter, TargetConfig};
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Schema of the `--config` TOML file. Every field is optional; environment variables
+/// override whatever is set here.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    pub private_key: Option<String>,
+    pub chain_id: Option<u64>,
+    pub signature_type: Option<u8>,
+    pub funder_address: Option<String>,
+    pub clob_api_url: Option<String>,
+    pub ws_url: Option<String>,
+    pub data_api_url: Option<String>,
+    pub gamma_api_url: Option<String>,
+    pub rpc_url: Option<String>,
+    pub order_type: Option<String>,
+    pub order_timeout_secs: Option<u64>,
+    pub tick_size: Option<String>,
+    pub neg_risk: Option<bool>,
+    pub enable_copy_trading: Option<bool>,
+    pub backfill_max_age_secs: Option<u64>,
+    pub credential_path: Option<PathBuf>,
+    pub holdings_path: Option<PathBuf>,
+    pub targets_path: Option<PathBuf>,
+    pub debug: Option<bool>,
+    #[serde(default)]
+    pub targets: Vec<TargetConfig>,
+    #[serde(default)]
+    pub markets: MarketFilter,
+    #[serde(default)]
+    pub risk: FileRisk,
+    #[serde(default)]
+    pub redemption: FileRedemption,
+    #[serde(default)]
+    pub paper: FilePaper,
+    #[serde(default)]
+    pub slippage: FileSlippage,
+    #[serde(default)]
+    pub stale: FileStale,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileRisk {
+    pub max_order_amount: Option<f64>,
+    pub max_market_exposure: Option<f64>,
+    pub max_total_exposure: Option<f64>,
+    pub max_open_positions: Option<usize>,
+    pub max_daily_spend: Option<f64>,
+    pub max_daily_loss: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileRedemption {
+    pub interval_minutes: Option<u64>,
+    pub max_retries: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FilePaper {
+    pub enabled: Option<bool>,
+    pub starting_balance: Option<f64>,
+    pub holdings_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileSlippage {
+    pub max_cents: Option<f64>,
+    pub max_percent: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileStale {
+    pub max_age_secs: Option<u64>,
+    pub max_midpoint_drift: Option<f64>,
+}
+
+/// Resolves fields from the environment (first) and the config file, collecting one error
+/// per invalid field instead of stopping at the first.
+pub(super) struct Fields<'a> {
+    env: &'a dyn Fn(&str) -> Option<String>,
+    pub errors: Vec<String>,
+}
+
+impl<'a> Fields<'a> {
+    pub fn new(env: &'a dyn Fn(&str) -> Option<String>) -> Self {
+        Self { env, errors: Vec::new() }
+    }
+
+    /// Trimmed env value; empty counts as unset.
+    pub fn env(&self, key: &str) -> Option<String> {
+        (self.env)(key).map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+    }
+
+    pub fn string(&self, key: &str, file: Option<String>) -> Option<String> {
+        self.env(key).or(file)
+    }
+
+    /// Env value parsed as `T` if set, else the file value.
+    pub fn number<T: FromStr>(&mut self, field: &str, key: &str, file: Option<T>) -> Option<T> {
+        match self.env(key) {
+            Some(raw) => match raw.parse() {
+                Ok(v) => Some(v),
+                Err(_) => {
+                    self.errors.push(format!("{} (env {}): expected a number, got {:?}", field, key, raw));
+                    None
+                }
+            },
+            None => file,
+        }
+    }
+
+    pub fn bool(&mut self, field: &str, key: &str, file: Option<bool>) -> Option<bool> {
+        match self.env(key) {
+            Some(raw) => match raw.to_lowercase().as_str() {
+                "true" | "1" => Some(true),
+                "false" | "0" => Some(false),
+                _ => {
+                    self.errors.push(format!("{} (env {}): expected true or false, got {:?}", field, key, raw));
+                    None
+                }
+            },
+            None => file,
+        }
+    }
+
+    /// Env or file string mapped through `parse`; `expected` describes the accepted values.
+    pub fn choice<T>(
+        &mut self,
+        field: &str,
+        key: &str,
+        file: Option<String>,
+        expected: &str,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> Option<T> {
+        let (raw, source) = match self.env(key) {
+            Some(raw) => (raw, format!("env {}", key)),
+            None => (file?, "config file".to_string()),
+        };
+        let parsed = parse(&raw);
+        if parsed.is_none() {
+            self.errors.push(format!("{} ({}): expected {}, got {:?}", field, source, expected, raw));
+        }
+        parsed
+    }
+
+    pub fn error(&mut self, msg: String) {
+        self.errors.push(msg);
+    }
+}
@@ -0,0 +1,731 @@
+use crate::feed::TradePayload;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize, Serializer};
+use std::env;
+use std::path::{Path, PathBuf};
+
+mod file;
+
+pub use file::{FileConfig, FilePaper, FileRedemption, FileRisk, FileSlippage, FileStale};
+
+use file::Fields;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Config {
+    pub private_key: String,
+    pub chain_id: u64,
+    /// How orders are signed: 0 for an EOA, 1 for a Polymarket (email/Magic) proxy wallet,
+    /// 2 for a Gnosis Safe proxy.
+    pub signature_type: u8,
+    /// Proxy wallet that holds the funds and makes the orders; required for signature types
+    /// 1 and 2.
+    pub funder_address: Option<String>,
+    pub clob_api_url: String,
+    pub ws_url: String,
+    pub data_api_url: String,
+    /// Gamma API, for market tags and end dates used by market filters.
+    pub gamma_api_url: String,
+    pub rpc_url: String,
+    pub order_type: OrderType,
+    /// GTC/GTD orders still unfilled after this long are cancelled.
+    pub order_timeout_secs: u64,
+    pub tick_size: TickSize,
+    pub neg_risk: bool,
+    pub enable_copy_trading: bool,
+    pub backfill_max_age_secs: u64,
+    pub credential_path: PathBuf,
+    pub holdings_path: PathBuf,
+    pub debug: bool,
+    /// Wallets to copy, each with its own sizing rules.
+    pub targets: Vec<TargetConfig>,
+    /// Market filter applied to every target's BUYs, before the target's own `markets`.
+    pub markets: MarketFilter,
+    pub risk: RiskConfig,
+    pub redemption: RedemptionConfig,
+    pub paper: PaperConfig,
+    pub slippage: SlippageConfig,
+    pub stale: StaleConfig,
+}
+
+/// Limits applied to every copied order, on top of the per-target rules.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RiskConfig {
+    pub max_order_amount: Option<f64>,
+    /// USDC cost of open positions in one market.
+    pub max_market_exposure: Option<f64>,
+    /// USDC cost of all open positions.
+    pub max_total_exposure: Option<f64>,
+    pub max_open_positions: Option<usize>,
+    /// USDC spent on buys in any rolling 24 hours.
+    pub max_daily_spend: Option<f64>,
+    /// Realized loss in a UTC day after which buys stop until the next day.
+    pub max_daily_loss: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RedemptionConfig {
+    /// Auto-redeem interval while the bot runs; `None` disables the loop.
+    pub interval_minutes: Option<u64>,
+    pub max_retries: u32,
+}
+
+/// Paper trading: simulated fills against the live book, tracked in separate files.
+#[derive(Debug, Clone, Serialize)]
+pub struct PaperConfig {
+    pub enabled: bool,
+    pub starting_balance: f64,
+    /// Paper holdings file; the simulated account is kept next to it.
+    pub holdings_path: PathBuf,
+}
+
+/// How much worse than the target's price a copied order may fill. Unset means no worse
+/// than the target's price.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct SlippageConfig {
+    /// Allowance in cents per share (0.01 USDC).
+    pub max_cents: Option<f64>,
+    /// Allowance as a percentage of the target's price.
+    pub max_percent: Option<f64>,
+}
+
+impl SlippageConfig {
+    /// The allowed price difference from `reference`.
+    pub fn allowance(&self, reference: f64) -> f64 {
+        match (self.max_cents, self.max_percent) {
+            (Some(cents), _) => cents / 100.0,
+            (None, Some(percent)) => reference * percent / 100.0,
+            (None, None) => 0.0,
+        }
+    }
+}
+
+/// When a BUY detected late is still copied.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct StaleConfig {
+    /// BUYs detected more than this long after execution are stale; unset disables the guard.
+    pub max_age_secs: Option<u64>,
+    /// Copy a stale BUY anyway if the current midpoint is within this of the target's price.
+    pub max_midpoint_drift: Option<f64>,
+}
+
+/// A copied wallet and the rules applied to its trades.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TargetConfig {
+    pub wallet: String,
+    /// Name used in logs; defaults to the wallet address.
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default = "default_size_multiplier")]
+    pub size_multiplier: f64,
+    /// How BUYs are sized; `size_multiplier` alone is the `multiplier` strategy.
+    #[serde(default)]
+    pub sizing: Option<Sizing>,
+    #[serde(default)]
+    pub max_order_amount: Option<f64>,
+    #[serde(default)]
+    pub markets: MarketFilter,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// How the target's SELLs are copied.
+    #[serde(default)]
+    pub sell_mode: SellMode,
+    /// In proportional mode, a sell that would leave fewer shares than this closes the whole
+    /// position instead (smaller remainders are below the exchange's minimum order size).
+    #[serde(default = "default_min_remaining_shares")]
+    pub min_remaining_shares: f64,
+}
+
+fn default_size_multiplier() -> f64 {
+    1.0
+}
+
+fn default_min_remaining_shares() -> f64 {
+    5.0
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl TargetConfig {
+    /// A target with default rules.
+    pub fn new(wallet: impl Into<String>) -> Self {
+        Self {
+            wallet: wallet.into(),
+            label: None,
+            size_multiplier: default_size_multiplier(),
+            sizing: None,
+            max_order_amount: None,
+            markets: MarketFilter::default(),
+            enabled: default_enabled(),
+            sell_mode: SellMode::default(),
+            min_remaining_shares: default_min_remaining_shares(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.wallet)
+    }
+
+    /// The configured sizing, or the multiplier strategy with `size_multiplier`.
+    pub fn sizing(&self) -> Sizing {
+        self.sizing.clone().unwrap_or(Sizing::Multiplier {
+            multiplier: self.size_multiplier,
+        })
+    }
+
+    pub fn is_wallet(&self, wallet: &str) -> bool {
+        self.wallet.eq_ignore_ascii_case(wallet)
+    }
+
+    /// This target with its order cap tightened to `cap` (the global risk limit).
+    pub fn with_max_order_cap(&self, cap: Option<f64>) -> Self {
+        let max_order_amount = match (self.max_order_amount, cap) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        Self {
+            max_order_amount,
+            ..self.clone()
+        }
+    }
+}
+
+/// Position-sizing strategy for a target's BUYs, e.g. `sizing = { strategy = "fixed_usd", usd = 10 }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Sizing {
+    /// The target's USDC amount times `multiplier`.
+    Multiplier { multiplier: f64 },
+    /// The same USDC amount on every trade.
+    FixedUsd { usd: f64 },
+    /// `percent` of our available USDC balance.
+    BalancePercent { percent: f64 },
+    /// The target's USDC amount scaled by our equity / the target's equity, times `multiplier`;
+    /// equity is USDC plus the value of open positions for both.
+    PortfolioProportional {
+        #[serde(default = "default_size_multiplier")]
+        multiplier: f64,
+    },
+}
+
+/// How a target's SELL is sized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SellMode {
+    /// Sell the same fraction of our copied shares as the target sold of its position.
+    #[default]
+    Proportional,
+    /// Sell everything copied from the target on any SELL.
+    Full,
+}
+
+impl SellMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "proportional" => Some(Self::Proportional),
+            "full" => Some(Self::Full),
+            _ => None,
+        }
+    }
+}
+
+/// Which markets a target's BUYs are copied in. Every rule that is set must pass; see
+/// [`crate::filter`] for how they are evaluated.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MarketFilter {
+    /// Case-insensitive keywords matched against the market slug and title.
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Case-insensitive regexes matched against the market slug and title.
+    #[serde(default)]
+    pub include_regex: Vec<String>,
+    #[serde(default)]
+    pub exclude_regex: Vec<String>,
+    /// When set, only these markets are copied.
+    #[serde(default)]
+    pub condition_ids: Vec<String>,
+    #[serde(default)]
+    pub exclude_condition_ids: Vec<String>,
+    /// Gamma tag labels or slugs, e.g. `sports`.
+    #[serde(default)]
+    pub include_tags: Vec<String>,
+    #[serde(default)]
+    pub exclude_tags: Vec<String>,
+    /// Band for the target's trade price.
+    #[serde(default)]
+    pub min_price: Option<f64>,
+    #[serde(default)]
+    pub max_price: Option<f64>,
+    /// Skip markets whose Gamma end date is less than this many hours away.
+    #[serde(default)]
+    pub min_hours_to_end: Option<f64>,
+}
+
+impl MarketFilter {
+    /// Whether `trade` passes the rules that need no market metadata (tags and end date are
+    /// not checked). Invalid regexes never match.
+    pub fn allows(&self, trade: &TradePayload) -> bool {
+        crate::filter::CompiledFilter::new(self).is_ok_and(|f| f.check(trade, None).is_ok())
+    }
+}
+
+fn validate_market_filter(f: &mut Fields, field: &str, filter: &MarketFilter) {
+    for pattern in filter.include_regex.iter().chain(&filter.exclude_regex) {
+        if let Err(e) = crate::filter::build_regex(pattern) {
+            f.error(format!("{}: invalid regex {:?}: {}", field, pattern, e));
+        }
+    }
+    for (name, price) in [("min_price", filter.min_price), ("max_price", filter.max_price)] {
+        if price.is_some_and(|p| !(0.0..=1.0).contains(&p)) {
+            f.error(format!("{}.{}: expected a price between 0 and 1", field, name));
+        }
+    }
+    if let (Some(min), Some(max)) = (filter.min_price, filter.max_price) {
+        if min > max {
+            f.error(format!("{}: min_price {} is above max_price {}", field, min, max));
+        }
+    }
+    if filter.min_hours_to_end.is_some_and(|h| h.is_nan() || h < 0.0) {
+        f.error(format!("{}.min_hours_to_end: must not be negative", field));
+    }
+}
+
+/// Reads the targets file (a JSON array of [`TargetConfig`]) if it exists.
+fn load_targets(path: &Path) -> Result<Option<Vec<TargetConfig>>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let s = std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    let targets: Vec<TargetConfig> = serde_json::from_str(&s).with_context(|| format!("parse {}", path.display()))?;
+    Ok(Some(targets))
+}
+
+/// FOK and FAK are market orders; GTC and GTD are limit orders resting at the target's price
+/// (GTD also expires on the exchange).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderType {
+    FOK,
+    FAK,
+    GTC,
+    GTD,
+}
+
+impl OrderType {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_uppercase().as_str() {
+            "FOK" => Some(OrderType::FOK),
+            "FAK" => Some(OrderType::FAK),
+            "GTC" => Some(OrderType::GTC),
+            "GTD" => Some(OrderType::GTD),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderType::FOK => "FOK",
+            OrderType::FAK => "FAK",
+            OrderType::GTC => "GTC",
+            OrderType::GTD => "GTD",
+        }
+    }
+
+    pub fn is_limit(&self) -> bool {
+        matches!(self, OrderType::GTC | OrderType::GTD)
+    }
+}
+
+impl Serialize for OrderType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickSize {
+    Tick01,
+    Tick001,
+    Tick0001,
+    Tick00001,
+}
+
+impl TickSize {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "0.1" => Some(TickSize::Tick01),
+            "0.01" => Some(TickSize::Tick001),
+            "0.001" => Some(TickSize::Tick0001),
+            "0.0001" => Some(TickSize::Tick00001),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TickSize::Tick01 => "0.1",
+            TickSize::Tick001 => "0.01",
+            TickSize::Tick0001 => "0.001",
+            TickSize::Tick00001 => "0.0001",
+        }
+    }
+}
+
+impl Serialize for TickSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl Config {
+    pub fn enabled_target_wallets(&self) -> Vec<String> {
+        self.targets.iter().filter(|t| t.enabled).map(|t| t.wallet.clone()).collect()
+    }
+
+    /// Environment (and `.env`) only, as before `--config` existed.
+    pub fn from_env() -> Result<Self> {
+        Self::load(None)
+    }
+
+    /// Reads the optional TOML config file, then applies environment overrides (including `.env`).
+    /// YAML files are rejected rather than misread as TOML.
+    pub fn load(config_path: Option<&Path>) -> Result<Self> {
+        dotenvy::dotenv().ok();
+        if let Some(ext) = config_path.and_then(|p| p.extension()).and_then(|e| e.to_str()) {
+            if ext.eq_ignore_ascii_case("yaml") || ext.eq_ignore_ascii_case("yml") {
+                anyhow::bail!("Unsupported config format .{}: only TOML config files are supported", ext);
+            }
+        }
+        let file = match config_path {
+            Some(path) => std::fs::read_to_string(path).with_context(|| format!("read config {}", path.display()))?,
+            None => String::new(),
+        };
+        Self::resolve(&file, |key| env::var(key).ok()).with_context(|| match config_path {
+            Some(path) => format!("Invalid configuration ({})", path.display()),
+            None => "Invalid configuration".to_string(),
+        })
+    }
+
+    /// Builds the config from TOML text and an env lookup. Env values win over file values;
+    /// every invalid field is reported, not just the first.
+    pub fn resolve(toml_text: &str, env: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let file: FileConfig = toml::from_str(toml_text).map_err(|e| anyhow::anyhow!("config file: {}", e))?;
+        let mut f = Fields::new(&env);
+
+        let private_key = f.string("PRIVATE_KEY", file.private_key).unwrap_or_default();
+        let chain_id = f.number("chain_id", "CHAIN_ID", file.chain_id).unwrap_or(137);
+        let signature_type = f
+            .number("signature_type", "SIGNATURE_TYPE", file.signature_type)
+            .unwrap_or(0);
+        let funder_address = f.string("FUNDER_ADDRESS", file.funder_address);
+
+        let clob_api_url = f
+            .string("CLOB_API_URL", file.clob_api_url)
+            .unwrap_or_else(|| "https://clob.polymarket.com".to_string());
+        let ws_url = f
+            .string("USER_REAL_TIME_DATA_URL", file.ws_url)
+            .unwrap_or_else(|| "wss://ws-live-data.polymarket.com".to_string());
+        let data_api_url = f
+            .string("DATA_API_URL", file.data_api_url)
+            .unwrap_or_else(|| "https://data-api.polymarket.com".to_string());
+        let gamma_api_url = f
+            .string("GAMMA_API_URL", file.gamma_api_url)
+            .unwrap_or_else(|| "https://gamma-api.polymarket.com".to_string());
+        let markets = MarketFilter {
+            min_price: f.number("markets.min_price", "FILTER_MIN_PRICE", file.markets.min_price),
+            max_price: f.number("markets.max_price", "FILTER_MAX_PRICE", file.markets.max_price),
+            min_hours_to_end: f.number("markets.min_hours_to_end", "FILTER_MIN_HOURS_TO_END", file.markets.min_hours_to_end),
+            ..file.markets
+        };
+        let rpc_url = f
+            .env("RPC_URL")
+            .or_else(|| f.env("RPC_TOKEN"))
+            .or(file.rpc_url)
+            .unwrap_or_else(|| {
+                if chain_id == 80002 {
+                    "https://rpc-amoy.polygon.technology".to_string()
+                } else {
+                    "https://polygon-rpc.com".to_string()
+                }
+            });
+
+        let order_type = f
+            .choice("order_type", "ORDER_TYPE", file.order_type, "FOK, FAK, GTC or GTD", OrderType::parse)
+            .unwrap_or(OrderType::FAK);
+        let order_timeout_secs = f
+            .number("order_timeout_secs", "ORDER_TIMEOUT_SECS", file.order_timeout_secs)
+            .unwrap_or(300);
+        let tick_size = f
+            .choice("tick_size", "TICK_SIZE", file.tick_size, "0.1, 0.01, 0.001 or 0.0001", TickSize::parse)
+            .unwrap_or(TickSize::Tick001);
+
+        let neg_risk = f.bool("neg_risk", "NEG_RISK", file.neg_risk).unwrap_or(false);
+        let enable_copy_trading = f
+            .bool("enable_copy_trading", "ENABLE_COPY_TRADING", file.enable_copy_trading)
+            .unwrap_or(true);
+        let backfill_max_age_secs = f
+            .number("backfill_max_age_secs", "BACKFILL_MAX_AGE_SECS", file.backfill_max_age_secs)
+            .unwrap_or(120);
+        let debug = f.bool("debug", "DEBUG", file.debug).unwrap_or(false);
+
+        let base = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let data_dir = base.join("src").join("data");
+        let credential_path = f
+            .env("CREDENTIAL_PATH")
+            .map(PathBuf::from)
+            .or(file.credential_path)
+            .unwrap_or_else(|| data_dir.join("credential.json"));
+        let holdings_path = f
+            .env("HOLDINGS_PATH")
+            .map(PathBuf::from)
+            .or(file.holdings_path)
+            .unwrap_or_else(|| data_dir.join("token-holding.json"));
+        let targets_path = f
+            .env("TARGETS_PATH")
+            .map(PathBuf::from)
+            .or(file.targets_path)
+            .unwrap_or_else(|| data_dir.join("targets.json"));
+
+        let risk = RiskConfig {
+            max_order_amount: f.number("risk.max_order_amount", "MAX_ORDER_AMOUNT", file.risk.max_order_amount),
+            max_market_exposure: f.number("risk.max_market_exposure", "MAX_MARKET_EXPOSURE", file.risk.max_market_exposure),
+            max_total_exposure: f.number("risk.max_total_exposure", "MAX_TOTAL_EXPOSURE", file.risk.max_total_exposure),
+            max_open_positions: f.number("risk.max_open_positions", "MAX_OPEN_POSITIONS", file.risk.max_open_positions),
+            max_daily_spend: f.number("risk.max_daily_spend", "MAX_DAILY_SPEND", file.risk.max_daily_spend),
+            max_daily_loss: f.number("risk.max_daily_loss", "MAX_DAILY_LOSS", file.risk.max_daily_loss),
+        };
+        let redemption = RedemptionConfig {
+            interval_minutes: f.number("redemption.interval_minutes", "REDEEM_DURATION", file.redemption.interval_minutes),
+            max_retries: f
+                .number("redemption.max_retries", "REDEEM_MAX_RETRIES", file.redemption.max_retries)
+                .unwrap_or(3),
+        };
+
+        let slippage = SlippageConfig {
+            max_cents: f.number("slippage.max_cents", "MAX_SLIPPAGE_CENTS", file.slippage.max_cents),
+            max_percent: f.number("slippage.max_percent", "MAX_SLIPPAGE_PERCENT", file.slippage.max_percent),
+        };
+        let stale = StaleConfig {
+            max_age_secs: f.number("stale.max_age_secs", "STALE_MAX_AGE_SECS", file.stale.max_age_secs),
+            max_midpoint_drift: f.number("stale.max_midpoint_drift", "STALE_MAX_MIDPOINT_DRIFT", file.stale.max_midpoint_drift),
+        };
+        let paper = PaperConfig {
+            enabled: f.bool("paper.enabled", "PAPER_TRADING", file.paper.enabled).unwrap_or(false),
+            starting_balance: f
+                .number("paper.starting_balance", "PAPER_STARTING_BALANCE", file.paper.starting_balance)
+                .unwrap_or(1000.0),
+            holdings_path: f
+                .env("PAPER_HOLDINGS_PATH")
+                .map(PathBuf::from)
+                .or(file.paper.holdings_path)
+                .unwrap_or_else(|| data_dir.join("paper").join("token-holding.json")),
+        };
+
+        let size_multiplier = f.number("size_multiplier", "SIZE_MULTIPLIER", None);
+        let sell_mode = f.choice("sell_mode", "SELL_MODE", None, "proportional or full", SellMode::parse);
+        let min_remaining_shares = f.number("min_remaining_shares", "MIN_REMAINING_SHARES", None);
+        // Targets: [[targets]] in the config file, else the targets JSON file, else TARGET_WALLET.
+        let mut targets = if !file.targets.is_empty() {
+            if f.env("TARGET_WALLET").is_some() {
+                f.error("targets (env TARGET_WALLET): conflicts with [[targets]] in the config file; set only one".to_string());
+            }
+            file.targets
+        } else {
+            match load_targets(&targets_path) {
+                Ok(Some(targets)) => targets,
+                Ok(None) => match f.env("TARGET_WALLET") {
+                    Some(wallet) => vec![TargetConfig::new(wallet)],
+                    None => Vec::new(),
+                },
+                Err(e) => {
+                    f.error(format!("targets_path: {:#}", e));
+                    Vec::new()
+                }
+            }
+        };
+        // SIZE_MULTIPLIER, SELL_MODE and MIN_REMAINING_SHARES override every target's value.
+        for target in &mut targets {
+            if let Some(m) = size_multiplier {
+                target.size_multiplier = m;
+            }
+            if let Some(mode) = sell_mode {
+                target.sell_mode = mode;
+            }
+            if let Some(min) = min_remaining_shares {
+                target.min_remaining_shares = min;
+            }
+        }
+
+        let config = Config {
+            private_key,
+            chain_id,
+            signature_type,
+            funder_address,
+            clob_api_url,
+            ws_url,
+            data_api_url,
+            gamma_api_url,
+            rpc_url,
+            order_type,
+            order_timeout_secs,
+            tick_size,
+            neg_risk,
+            enable_copy_trading,
+            backfill_max_age_secs,
+            credential_path,
+            holdings_path,
+            debug,
+            targets,
+            markets,
+            risk,
+            redemption,
+            paper,
+            slippage,
+            stale,
+        };
+        config.validate(&mut f);
+        if !f.errors.is_empty() {
+            anyhow::bail!(
+                "{} invalid setting(s):{}",
+                f.errors.len(),
+                f.errors.iter().map(|e| format!("\n  - {}", e)).collect::<String>()
+            );
+        }
+        Ok(config)
+    }
+
+    fn validate(&self, f: &mut Fields) {
+        validate_market_filter(f, "markets", &self.markets);
+        let key = self.private_key.trim_start_matches("0x");
+        if self.private_key.is_empty() {
+            f.error("private_key (env PRIVATE_KEY): not set".to_string());
+        } else if key.len() != 64 || hex::decode(key).is_err() {
+            f.error("private_key (env PRIVATE_KEY): expected 32 bytes of hex".to_string());
+        }
+        if self.chain_id != 137 && self.chain_id != 80002 {
+            f.error(format!("chain_id: expected 137 (Polygon) or 80002 (Amoy), got {}", self.chain_id));
+        }
+        match (self.signature_type, &self.funder_address) {
+            (0, _) => {}
+            (1 | 2, None) => f.error(format!(
+                "funder_address (env FUNDER_ADDRESS): required with signature_type {}",
+                self.signature_type
+            )),
+            (1 | 2, Some(_)) => {}
+            (other, _) => f.error(format!(
+                "signature_type (env SIGNATURE_TYPE): expected 0 (EOA), 1 (proxy) or 2 (Gnosis Safe), got {}",
+                other
+            )),
+        }
+        if let Some(funder) = &self.funder_address {
+            if crate::chain::address_from_hex(funder).is_err() {
+                f.error(format!("funder_address (env FUNDER_ADDRESS): not an address: {}", funder));
+            }
+        }
+        for (field, url) in [
+            ("clob_api_url", &self.clob_api_url),
+            ("ws_url", &self.ws_url),
+            ("data_api_url", &self.data_api_url),
+            ("gamma_api_url", &self.gamma_api_url),
+            ("rpc_url", &self.rpc_url),
+        ] {
+            if url::Url::parse(url).is_err() {
+                f.error(format!("{}: not a valid URL", field));
+            }
+        }
+        if self.targets.is_empty() {
+            f.error("targets: no target wallet configured (set TARGET_WALLET or add [[targets]])".to_string());
+        }
+        for (i, t) in self.targets.iter().enumerate() {
+            let w = t.wallet.trim_start_matches("0x");
+            if !t.wallet.starts_with("0x") || w.len() != 40 || hex::decode(w).is_err() {
+                f.error(format!("targets[{}].wallet: expected a 0x-prefixed address, got {:?}", i, t.wallet));
+            }
+            if !(t.size_multiplier.is_finite() && t.size_multiplier > 0.0) {
+                f.error(format!("targets[{}].size_multiplier: must be greater than 0, got {}", i, t.size_multiplier));
+            }
+            if t.max_order_amount.is_some_and(|m| m.is_nan() || m <= 0.0) {
+                f.error(format!("targets[{}].max_order_amount: must be greater than 0", i));
+            }
+            match t.sizing {
+                Some(Sizing::Multiplier { multiplier: v })
+                | Some(Sizing::FixedUsd { usd: v })
+                | Some(Sizing::PortfolioProportional { multiplier: v })
+                    if !(v.is_finite() && v > 0.0) =>
+                {
+                    f.error(format!("targets[{}].sizing: must be greater than 0, got {}", i, v));
+                }
+                Some(Sizing::BalancePercent { percent }) if !(percent > 0.0 && percent <= 100.0) => {
+                    f.error(format!("targets[{}].sizing.percent: expected more than 0 and at most 100, got {}", i, percent));
+                }
+                _ => {}
+            }
+            if t.min_remaining_shares.is_nan() || t.min_remaining_shares < 0.0 {
+                f.error(format!("targets[{}].min_remaining_shares: must not be negative", i));
+            }
+            validate_market_filter(f, &format!("targets[{}].markets", i), &t.markets);
+            if self.targets[..i].iter().any(|o| o.is_wallet(&t.wallet)) {
+                f.error(format!("targets[{}].wallet: {} is listed more than once", i, t.wallet));
+            }
+        }
+        for (field, value) in [
+            ("max_order_amount", self.risk.max_order_amount),
+            ("max_market_exposure", self.risk.max_market_exposure),
+            ("max_total_exposure", self.risk.max_total_exposure),
+            ("max_daily_spend", self.risk.max_daily_spend),
+            ("max_daily_loss", self.risk.max_daily_loss),
+        ] {
+            if value.is_some_and(|m| m.is_nan() || m <= 0.0) {
+                f.error(format!("risk.{}: must be greater than 0", field));
+            }
+        }
+        if self.risk.max_open_positions == Some(0) {
+            f.error("risk.max_open_positions: must be greater than 0".to_string());
+        }
+        if self.paper.starting_balance.is_nan() || self.paper.starting_balance < 0.0 {
+            f.error("paper.starting_balance: must not be negative".to_string());
+        }
+        if self.paper.enabled && self.paper.holdings_path == self.holdings_path {
+            f.error("paper.holdings_path: must differ from holdings_path".to_string());
+        }
+        if self.slippage.max_cents.is_some() && self.slippage.max_percent.is_some() {
+            f.error("slippage: set max_cents or max_percent, not both".to_string());
+        }
+        if self.slippage.max_cents.is_some_and(|c| !(0.0..100.0).contains(&c)) {
+            f.error("slippage.max_cents: expected 0 to 100".to_string());
+        }
+        if self.slippage.max_percent.is_some_and(|p| p.is_nan() || p < 0.0) {
+            f.error("slippage.max_percent: must not be negative".to_string());
+        }
+        if self.stale.max_midpoint_drift.is_some_and(|d| !(0.0..1.0).contains(&d)) {
+            f.error("stale.max_midpoint_drift: expected a price difference from 0 to 1".to_string());
+        }
+        if self.order_timeout_secs == 0 {
+            f.error("order_timeout_secs: must be greater than 0".to_string());
+        }
+        if self.redemption.interval_minutes == Some(0) {
+            f.error("redemption.interval_minutes: must be greater than 0".to_string());
+        }
+    }
+
+    /// The resolved config as TOML, with the private key and any RPC URL path (API keys) hidden.
+    pub fn to_redacted_toml(&self) -> Result<String> {
+        let mut shown = self.clone();
+        shown.private_key = "<redacted>".to_string();
+        if let Ok(mut url) = url::Url::parse(&shown.rpc_url) {
+            if url.path() != "/" || url.query().is_some() {
+                url.set_path("***");
+                url.set_query(None);
+            }
+            // Credentials in the userinfo part are as secret as an API key in the path.
+            let _ = url.set_username("");
+            let _ = url.set_password(None);
+            shown.rpc_url = url.to_string();
+        }
+        Ok(toml::to_string_pretty(&shown)?)
+    }
+}
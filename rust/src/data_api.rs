@@ -0,0 +1,138 @@
+use crate::clob::client::http_client;
+use crate::feed::TradePayload;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use tracing::info;
+
+const POSITIONS_PAGE_LIMIT: usize = 500;
+const MAX_POSITIONS: usize = 1000;
+const ACTIVITY_PAGE_LIMIT: usize = 500;
+
+/// A current position as returned by the Data API `/positions` endpoint.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub proxy_wallet: Option<String>,
+    pub asset: String,
+    pub condition_id: String,
+    #[serde(default)]
+    pub size: f64,
+    #[serde(default)]
+    pub avg_price: f64,
+    #[serde(default)]
+    pub cur_price: f64,
+    #[serde(default)]
+    pub current_value: f64,
+    #[serde(default)]
+    pub redeemable: bool,
+    pub title: Option<String>,
+    pub slug: Option<String>,
+    pub outcome: Option<String>,
+    pub outcome_index: Option<u32>,
+    #[serde(default)]
+    pub negative_risk: bool,
+}
+
+/// Fetches all current positions of `user`, paging by offset like the TS `getMarketsWithUserPositions`.
+pub async fn get_positions(data_api_url: &str, user: &str) -> Result<Vec<Position>> {
+    let client = http_client();
+    let mut all: Vec<Position> = Vec::new();
+    let mut offset = 0;
+    while all.len() < MAX_POSITIONS {
+        let url = format!(
+            "{}/positions?user={}&limit={}&offset={}&sortBy=TOKENS&sortDirection=DESC&sizeThreshold=0",
+            data_api_url.trim_end_matches('/'),
+            user,
+            POSITIONS_PAGE_LIMIT,
+            offset
+        );
+        let res = client.get(&url).send().await.context("positions request")?;
+        let status = res.status();
+        if !status.is_success() {
+            let body = res.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to fetch positions: {} {}", status, body);
+        }
+        let page: Vec<Position> = res.json().await.context("parse positions response")?;
+        let n = page.len();
+        all.extend(page);
+        if n < POSITIONS_PAGE_LIMIT {
+            break;
+        }
+        offset += POSITIONS_PAGE_LIMIT;
+    }
+    info!("Fetched {} current position(s) for {}", all.len(), user);
+    Ok(all)
+}
+
+/// Current size of `user`'s position in one outcome token (0 if it has none).
+pub async fn get_position_size(data_api_url: &str, user: &str, condition_id: &str, token_id: &str) -> Result<f64> {
+    let url = format!(
+        "{}/positions?user={}&market={}&sizeThreshold=0",
+        data_api_url.trim_end_matches('/'),
+        user,
+        condition_id
+    );
+    let res = http_client().get(&url).send().await.context("positions request")?;
+    let status = res.status();
+    if !status.is_success() {
+        let body = res.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to fetch positions: {} {}", status, body);
+    }
+    let positions: Vec<Position> = res.json().await.context("parse positions response")?;
+    Ok(positions.iter().filter(|p| p.asset == token_id).map(|p| p.size).sum())
+}
+
+#[derive(Debug, Deserialize)]
+struct PortfolioValue {
+    #[serde(default)]
+    value: f64,
+}
+
+/// Current value of `user`'s open positions, from the Data API `/value` endpoint.
+pub async fn get_portfolio_value(data_api_url: &str, user: &str) -> Result<f64> {
+    let url = format!("{}/value?user={}", data_api_url.trim_end_matches('/'), user);
+    let res = http_client().get(&url).send().await.context("value request")?;
+    let status = res.status();
+    if !status.is_success() {
+        let body = res.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to fetch portfolio value: {} {}", status, body);
+    }
+    let values: Vec<PortfolioValue> = res.json().await.context("parse value response")?;
+    Ok(values.iter().map(|v| v.value).sum())
+}
+
+/// Trades made by `user` at or after `start_ts` (unix seconds), oldest first, from the
+/// Data API `/activity` endpoint. Items carry the same fields as live-feed trade payloads.
+/// Pages until a short page, so the result is never cut off at the newest trades.
+pub async fn get_trade_activity(data_api_url: &str, user: &str, start_ts: u64) -> Result<Vec<TradePayload>> {
+    let client = http_client();
+    let mut all: Vec<TradePayload> = Vec::new();
+    let mut offset = 0;
+    loop {
+        let url = format!(
+            "{}/activity?user={}&type=TRADE&start={}&limit={}&offset={}&sortBy=TIMESTAMP&sortDirection=ASC",
+            data_api_url.trim_end_matches('/'),
+            user,
+            start_ts,
+            ACTIVITY_PAGE_LIMIT,
+            offset
+        );
+        let res = client.get(&url).send().await.context("activity request")?;
+        let status = res.status();
+        if !status.is_success() {
+            let body = res.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to fetch activity: {} {}", status, body);
+        }
+        let page: Vec<TradePayload> = res.json().await.context("parse activity response")?;
+        let n = page.len();
+        all.extend(page);
+        if n < ACTIVITY_PAGE_LIMIT {
+            break;
+        }
+        offset += ACTIVITY_PAGE_LIMIT;
+    }
+    if offset > 0 {
+        info!("Fetched {} activity item(s) for {} over {} pages", all.len(), user, offset / ACTIVITY_PAGE_LIMIT + 1);
+    }
+    Ok(all)
+}
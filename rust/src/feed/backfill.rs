@@ -0,0 +1,77 @@
+use super::{timestamp_secs, TradePayload};
+use crate::data_api::get_trade_activity;
+use anyhow::Result;
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+const SEEN_TRADES_CAPACITY: usize = 10_000;
+
+/// Where to fetch missed trades from after a reconnect, and how old a missed trade may be
+/// before it is no longer worth copying.
+#[derive(Debug, Clone)]
+pub struct BackfillConfig {
+    pub data_api_url: String,
+    pub max_age: Duration,
+}
+
+/// Bounded set of transaction hashes already handled, oldest evicted first.
+#[derive(Debug, Default)]
+pub struct SeenTrades {
+    order: VecDeque<String>,
+    set: HashSet<String>,
+}
+
+impl SeenTrades {
+    /// Records `tx_hash`; returns false if it was already seen.
+    pub fn insert(&mut self, tx_hash: &str) -> bool {
+        let key = tx_hash.to_lowercase();
+        if !self.set.insert(key.clone()) {
+            return false;
+        }
+        self.order.push_back(key);
+        if self.order.len() > SEEN_TRADES_CAPACITY {
+            if let Some(old) = self.order.pop_front() {
+                self.set.remove(&old);
+            }
+        }
+        true
+    }
+
+    pub fn contains(&self, tx_hash: &str) -> bool {
+        self.set.contains(&tx_hash.to_lowercase())
+    }
+}
+
+/// Trades `target_wallet` made since `since_ts` that were not seen on the feed, oldest first.
+/// Trades older than `max_age` are marked seen and dropped.
+pub async fn backfill_trades(
+    cfg: &BackfillConfig,
+    target_wallet: &str,
+    since_ts: u64,
+    seen: &mut SeenTrades,
+) -> Result<Vec<TradePayload>> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let oldest_allowed = now.saturating_sub(cfg.max_age.as_secs());
+    let mut trades = get_trade_activity(&cfg.data_api_url, target_wallet, since_ts).await?;
+    trades.sort_by_key(|t| t.timestamp.map(timestamp_secs).unwrap_or(0));
+
+    let mut missed = Vec::new();
+    for trade in trades {
+        let tx = match trade.transaction_hash.as_deref() {
+            Some(tx) => tx,
+            None => continue,
+        };
+        if !seen.insert(tx) {
+            continue;
+        }
+        let ts = trade.timestamp.map(timestamp_secs).unwrap_or(0);
+        if ts < oldest_allowed {
+            warn!("Skipping stale missed trade {} ({}s old)", tx, now.saturating_sub(ts));
+            continue;
+        }
+        missed.push(trade);
+    }
+    info!("Backfill since {}: {} missed trade(s) to copy", since_ts, missed.len());
+    Ok(missed)
+}
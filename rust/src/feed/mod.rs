@@ -1,12 +1,19 @@
 use anyhow::Result;
 use futures_util::{SinkExt, StreamExt};
-use serde::Deserialize;
-use std::sync::atomic::{AtomicBool, Ordering};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
 use tracing::{info, warn};
 use url::Url;
 
-#[derive(Debug, Clone, Deserialize)]
+mod backfill;
+
+pub use backfill::{backfill_trades, BackfillConfig, SeenTrades};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradePayload {
     pub asset: String,
     pub condition_id: Option<String>,
@@ -31,6 +38,35 @@ impl TradePayload {
     }
 }
 
+/// Normalises a trade timestamp to unix milliseconds: the feed may send seconds or
+/// milliseconds, and anything past 10^12 is taken to be milliseconds.
+pub fn timestamp_millis(ts: u64) -> u64 {
+    if ts > 1_000_000_000_000 {
+        ts
+    } else {
+        ts.saturating_mul(1000)
+    }
+}
+
+/// Normalises a trade timestamp to unix seconds (see [`timestamp_millis`]).
+pub fn timestamp_secs(ts: u64) -> u64 {
+    timestamp_millis(ts) / 1000
+}
+
+/// Time from the trade's execution (its timestamp) to `now`: how far behind the bot is.
+pub fn trade_latency(trade: &TradePayload, now: SystemTime) -> Option<Duration> {
+    let ms = timestamp_millis(trade.timestamp?);
+    let now_ms = now.duration_since(UNIX_EPOCH).ok()?.as_millis() as u64;
+    Some(Duration::from_millis(now_ms.saturating_sub(ms)))
+}
+
+/// Feed position carried across reconnects: newest trade timestamp seen and, per target
+/// wallet (lowercased), the trades already handled.
+struct FeedCursor {
+    last_trade_ts: u64,
+    seen: HashMap<String, SeenTrades>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct WsMessage {
     pub topic: Option<String>,
@@ -39,11 +75,98 @@ pub struct WsMessage {
     pub payload: Option<serde_json::Value>,
 }
 
+/// Connection health counters for the live-data feed, shared with the caller for alerting.
+#[derive(Debug, Default)]
+pub struct FeedStats {
+    connected: AtomicBool,
+    reconnects: AtomicU64,
+    total_downtime_ms: AtomicU64,
+    last_downtime_ms: AtomicU64,
+    trades: AtomicU64,
+    total_latency_ms: AtomicU64,
+    max_latency_ms: AtomicU64,
+    last_latency_ms: AtomicU64,
+}
+
+impl FeedStats {
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    /// Successful connections after the first one.
+    pub fn reconnects(&self) -> u64 {
+        self.reconnects.load(Ordering::SeqCst)
+    }
+
+    pub fn total_downtime(&self) -> Duration {
+        Duration::from_millis(self.total_downtime_ms.load(Ordering::SeqCst))
+    }
+
+    pub fn last_downtime(&self) -> Duration {
+        Duration::from_millis(self.last_downtime_ms.load(Ordering::SeqCst))
+    }
+
+    /// Target trades detected (live or backfilled) that carried a timestamp.
+    pub fn trades(&self) -> u64 {
+        self.trades.load(Ordering::SeqCst)
+    }
+
+    /// Mean detection latency, see [`trade_latency`].
+    pub fn avg_latency(&self) -> Duration {
+        let n = self.trades();
+        Duration::from_millis(self.total_latency_ms.load(Ordering::SeqCst).checked_div(n).unwrap_or(0))
+    }
+
+    pub fn max_latency(&self) -> Duration {
+        Duration::from_millis(self.max_latency_ms.load(Ordering::SeqCst))
+    }
+
+    pub fn last_latency(&self) -> Duration {
+        Duration::from_millis(self.last_latency_ms.load(Ordering::SeqCst))
+    }
+
+    pub fn record_latency(&self, latency: Duration) {
+        let ms = latency.as_millis() as u64;
+        self.trades.fetch_add(1, Ordering::SeqCst);
+        self.total_latency_ms.fetch_add(ms, Ordering::SeqCst);
+        self.max_latency_ms.fetch_max(ms, Ordering::SeqCst);
+        self.last_latency_ms.store(ms, Ordering::SeqCst);
+    }
+
+    fn record_reconnect(&self, downtime: Duration) {
+        let ms = downtime.as_millis() as u64;
+        self.reconnects.fetch_add(1, Ordering::SeqCst);
+        self.total_downtime_ms.fetch_add(ms, Ordering::SeqCst);
+        self.last_downtime_ms.store(ms, Ordering::SeqCst);
+    }
+}
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// A connection that stayed up this long resets the backoff.
+const STABLE_CONNECTION: Duration = Duration::from_secs(60);
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+const PONG_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Exponential backoff with "equal jitter": a random delay in `[d/2, d]` for `d = initial * 2^attempt`.
+fn backoff_delay(attempt: u32) -> Duration {
+    let base = INITIAL_BACKOFF.saturating_mul(2u32.saturating_pow(attempt.min(16))).min(MAX_BACKOFF);
+    let jitter: f64 = ethers::core::rand::random();
+    base / 2 + base.mul_f64(jitter / 2.0)
+}
+
+/// Runs the live-data feed forever, reconnecting with jittered exponential backoff whenever the
+/// socket closes, errors or stops answering pings, and re-subscribing to `activity:trades`.
+/// Trades made by any of `target_wallets` are passed to `on_trade`. With `backfill`, trades the
+/// targets made while disconnected are fetched from the Data API after each reconnect and passed
+/// to `on_trade` like live ones.
 pub async fn run_feed<F, Fut>(
     ws_url: &str,
-    target_wallet: &str,
+    target_wallets: &[String],
     copy_trading_paused: &AtomicBool,
     enable_copy_trading: bool,
+    stats: &FeedStats,
+    backfill: Option<&BackfillConfig>,
     mut on_trade: F,
 ) -> Result<()>
 where
@@ -51,9 +174,104 @@ where
     Fut: std::future::Future<Output = Result<()>> + Send,
 {
     let url = Url::parse(ws_url)?;
-    let (ws, _) = connect_async(url).await?;
+    let mut attempt: u32 = 0;
+    let mut disconnected_at: Option<Instant> = None;
+    let mut cursor = FeedCursor {
+        last_trade_ts: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        seen: HashMap::new(),
+    };
+    loop {
+        let (ws, _) = match connect_async(url.clone()).await {
+            Ok(c) => c,
+            Err(e) => {
+                let delay = backoff_delay(attempt);
+                warn!("Feed connect failed: {}. Retrying in {:?}", e, delay);
+                attempt = attempt.saturating_add(1);
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+        };
+        stats.connected.store(true, Ordering::SeqCst);
+        if let Some(since) = disconnected_at.take() {
+            stats.record_reconnect(since.elapsed());
+            warn!(
+                "Feed reconnected: reconnects={} downtime={:?} total_downtime={:?}",
+                stats.reconnects(),
+                stats.last_downtime(),
+                stats.total_downtime()
+            );
+            if let Some(cfg) = backfill {
+                let since = cursor.last_trade_ts;
+                let mut missed = Vec::new();
+                for wallet in target_wallets {
+                    let seen = cursor.seen.entry(wallet.to_lowercase()).or_default();
+                    match backfill_trades(cfg, wallet, since, seen).await {
+                        Ok(trades) => missed.extend(trades),
+                        Err(e) => warn!("Backfill failed for {}: {}", wallet, e),
+                    }
+                }
+                missed.sort_by_key(|t| t.timestamp.map(timestamp_secs).unwrap_or(0));
+                for trade in missed {
+                    if let Some(ts) = trade.timestamp {
+                        cursor.last_trade_ts = cursor.last_trade_ts.max(timestamp_secs(ts));
+                    }
+                    if let Some(latency) = trade_latency(&trade, SystemTime::now()) {
+                        stats.record_latency(latency);
+                        info!("Backfilled trade {} detected {:?} after execution", trade.side, latency);
+                    }
+                    if enable_copy_trading && !copy_trading_paused.load(Ordering::SeqCst) {
+                        if let Err(e) = on_trade(trade).await {
+                            warn!("Copy trade error (backfill): {}", e);
+                        }
+                    }
+                }
+            }
+        } else {
+            info!("Connected to real-time feed");
+        }
+
+        let connected_at = Instant::now();
+        match run_connection(
+            ws,
+            target_wallets,
+            copy_trading_paused,
+            enable_copy_trading,
+            stats,
+            &mut cursor,
+            &mut on_trade,
+        )
+        .await
+        {
+            Ok(()) => warn!("Feed connection closed"),
+            Err(e) => warn!("Feed connection lost: {}", e),
+        }
+        stats.connected.store(false, Ordering::SeqCst);
+        disconnected_at = Some(Instant::now());
+
+        if connected_at.elapsed() >= STABLE_CONNECTION {
+            attempt = 0;
+        }
+        let delay = backoff_delay(attempt);
+        attempt = attempt.saturating_add(1);
+        info!("Reconnecting to feed in {:?}", delay);
+        tokio::time::sleep(delay).await;
+    }
+}
+
+async fn run_connection<F, Fut>(
+    ws: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    target_wallets: &[String],
+    copy_trading_paused: &AtomicBool,
+    enable_copy_trading: bool,
+    stats: &FeedStats,
+    cursor: &mut FeedCursor,
+    on_trade: &mut F,
+) -> Result<()>
+where
+    F: FnMut(TradePayload) -> Fut + Send,
+    Fut: std::future::Future<Output = Result<()>> + Send,
+{
     let (mut write, mut read) = ws.split();
-    info!("Connected to real-time feed");
 
     let sub = serde_json::json!({
         "auth": {},
@@ -63,49 +281,72 @@ where
     write.send(Message::Text(sub.to_string())).await?;
     info!("Subscribed to activity:trades");
 
-    while let Some(msg) = read.next().await {
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+    ping.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut awaiting_pong: Option<Instant> = None;
+
+    loop {
+        // Frames already received go first, so a buffered pong is seen before the ping tick
+        // checks the pong timeout.
+        let msg = tokio::select! {
+            biased;
+            msg = read.next() => msg,
+            _ = ping.tick() => {
+                if let Some(sent) = awaiting_pong {
+                    if sent.elapsed() >= PONG_TIMEOUT {
+                        anyhow::bail!("no pong within {:?}", PONG_TIMEOUT);
+                    }
+                    continue;
+                }
+                write.send(Message::Ping(Vec::new())).await?;
+                awaiting_pong = Some(Instant::now());
+                continue;
+            }
+        };
         let msg = match msg {
-            Ok(Message::Text(t)) => t,
-            Ok(Message::Ping(d)) => {
+            Some(Ok(Message::Text(t))) => t,
+            Some(Ok(Message::Ping(d))) => {
                 let _ = write.send(Message::Pong(d)).await;
                 continue;
             }
-            Ok(Message::Close(_)) => break,
-            Err(e) => {
-                warn!("WS error: {}", e);
-                break;
+            Some(Ok(Message::Pong(_))) => {
+                awaiting_pong = None;
+                continue;
             }
+            Some(Ok(Message::Close(_))) | None => return Ok(()),
+            Some(Err(e)) => return Err(e.into()),
             _ => continue,
         };
 
-        let parsed: WsMessage = match serde_json::from_str(&msg) {
-            Ok(p) => p,
-            _ => continue,
-        };
-        if parsed.topic.as_deref() != Some("activity") || parsed.msg_type.as_deref() != Some("trades") {
-            continue;
-        }
-        let payload: TradePayload = match parsed.payload {
-            Some(p) => match serde_json::from_value(p) {
-                Ok(pl) => pl,
-                _ => continue,
-            },
+        let payload = match parse_trade(&msg) {
+            Some(p) => p,
             None => continue,
         };
-        let proxy: String = match &payload.proxy_wallet {
-            Some(w) => w.to_lowercase(),
+        if let Some(ts) = payload.timestamp {
+            cursor.last_trade_ts = cursor.last_trade_ts.max(timestamp_secs(ts));
+        }
+        let wallet = match matching_wallet(&payload, target_wallets) {
+            Some(w) => w,
             None => continue,
         };
-        if proxy != target_wallet.to_lowercase() {
-            continue;
+        if let Some(tx) = payload.transaction_hash.as_deref() {
+            if !cursor.seen.entry(wallet.to_lowercase()).or_default().insert(tx) {
+                continue;
+            }
         }
 
+        let latency = trade_latency(&payload, SystemTime::now());
+        if let Some(latency) = latency {
+            stats.record_latency(latency);
+        }
         info!(
-            "Trade detected: side={} price={} size={} market={}",
+            "Trade detected: target={} side={} price={} size={} market={} latency={:?}",
+            wallet,
             payload.side,
             payload.price,
             payload.size,
-            payload.title.as_deref().unwrap_or("")
+            payload.title.as_deref().unwrap_or(""),
+            latency.unwrap_or_default()
         );
 
         if enable_copy_trading && !copy_trading_paused.load(Ordering::SeqCst) {
@@ -114,5 +355,18 @@ where
             }
         }
     }
-    Ok(())
+}
+
+/// Returns the trade payload of an `activity:trades` message.
+fn parse_trade(msg: &str) -> Option<TradePayload> {
+    let parsed: WsMessage = serde_json::from_str(msg).ok()?;
+    if parsed.topic.as_deref() != Some("activity") || parsed.msg_type.as_deref() != Some("trades") {
+        return None;
+    }
+    serde_json::from_value(parsed.payload?).ok()
+}
+
+fn matching_wallet<'a>(payload: &TradePayload, wallets: &'a [String]) -> Option<&'a str> {
+    let trader = payload.proxy_wallet.as_deref()?;
+    wallets.iter().find(|w| w.eq_ignore_ascii_case(trader)).map(String::as_str)
 }
@@ -0,0 +1,206 @@
+use crate::config::{MarketFilter, TargetConfig};
+use crate::feed::TradePayload;
+use crate::gamma::{get_gamma_market, GammaMarket};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// How long Gamma metadata of a market is reused.
+const METADATA_TTL: Duration = Duration::from_secs(600);
+
+/// Filter regexes are case-insensitive.
+pub fn build_regex(pattern: &str) -> std::result::Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(true).build()
+}
+
+/// A [`MarketFilter`] with its regexes compiled.
+#[derive(Debug, Clone)]
+pub struct CompiledFilter {
+    filter: MarketFilter,
+    include_regex: Vec<Regex>,
+    exclude_regex: Vec<Regex>,
+}
+
+impl CompiledFilter {
+    pub fn new(filter: &MarketFilter) -> Result<Self> {
+        let compile = |patterns: &[String]| patterns.iter().map(|p| build_regex(p)).collect::<Result<Vec<_>, _>>();
+        Ok(Self {
+            filter: filter.clone(),
+            include_regex: compile(&filter.include_regex)?,
+            exclude_regex: compile(&filter.exclude_regex)?,
+        })
+    }
+
+    /// Whether any rule needs Gamma metadata (tags or end date).
+    pub fn needs_metadata(&self) -> bool {
+        let f = &self.filter;
+        !f.include_tags.is_empty() || !f.exclude_tags.is_empty() || f.min_hours_to_end.is_some()
+    }
+
+    pub fn check(&self, trade: &TradePayload, market: Option<&GammaMarket>) -> std::result::Result<(), String> {
+        self.check_at(trade, market, Utc::now())
+    }
+
+    /// `Err` names the rule that rejected `trade`. Tag and end-date rules are skipped when
+    /// `market` is `None`, and the end-date rule when the market has no end date.
+    pub fn check_at(
+        &self,
+        trade: &TradePayload,
+        market: Option<&GammaMarket>,
+        now: DateTime<Utc>,
+    ) -> std::result::Result<(), String> {
+        let f = &self.filter;
+        let condition_id = trade.condition_id().unwrap_or("");
+        if !f.condition_ids.is_empty() && !f.condition_ids.iter().any(|c| c.eq_ignore_ascii_case(condition_id)) {
+            return Err(format!("condition_ids: {} is not listed", condition_id));
+        }
+        if let Some(c) = f.exclude_condition_ids.iter().find(|c| c.eq_ignore_ascii_case(condition_id)) {
+            return Err(format!("exclude_condition_ids: {}", c));
+        }
+
+        let text = format!(
+            "{} {}",
+            trade.slug.as_deref().unwrap_or(""),
+            trade.title.as_deref().unwrap_or("")
+        );
+        let lower = text.to_lowercase();
+        let keyword = |k: &&String| lower.contains(&k.to_lowercase());
+        if !f.include.is_empty() && !f.include.iter().any(|k| keyword(&k)) {
+            return Err("include: no keyword matched".to_string());
+        }
+        if let Some(k) = f.exclude.iter().find(keyword) {
+            return Err(format!("exclude: {:?}", k));
+        }
+        if !self.include_regex.is_empty() && !self.include_regex.iter().any(|r| r.is_match(&text)) {
+            return Err("include_regex: no pattern matched".to_string());
+        }
+        if let Some(r) = self.exclude_regex.iter().find(|r| r.is_match(&text)) {
+            return Err(format!("exclude_regex: {:?}", r.as_str()));
+        }
+
+        if let Some(min) = f.min_price.filter(|min| trade.price < *min) {
+            return Err(format!("min_price: {} is below {}", trade.price, min));
+        }
+        if let Some(max) = f.max_price.filter(|max| trade.price > *max) {
+            return Err(format!("max_price: {} is above {}", trade.price, max));
+        }
+
+        let Some(market) = market else {
+            return Ok(());
+        };
+        if !f.include_tags.is_empty() && !f.include_tags.iter().any(|t| market.has_tag(t)) {
+            return Err("include_tags: no tag matched".to_string());
+        }
+        if let Some(t) = f.exclude_tags.iter().find(|t| market.has_tag(t)) {
+            return Err(format!("exclude_tags: {:?}", t));
+        }
+        if let (Some(min_hours), Some(end)) = (f.min_hours_to_end, market.end_time()) {
+            let hours = (end - now).num_seconds() as f64 / 3600.0;
+            if hours < min_hours {
+                return Err(format!("min_hours_to_end: ends in {:.1}h, under {}h", hours, min_hours));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The stage between the feed and copying: the global `markets` filter, then the trade's
+/// target's own. Only BUYs are filtered, so positions can always be exited. Gamma metadata
+/// is fetched only when a filter has tag or end-date rules; a trade whose metadata cannot be
+/// fetched is skipped. Without a Gamma URL (backtests) those rules are ignored.
+pub struct TradeFilter {
+    global: CompiledFilter,
+    /// lowercased wallet -> the target's filter
+    targets: HashMap<String, CompiledFilter>,
+    gamma_api_url: Option<String>,
+    cache: Mutex<HashMap<String, (Instant, Option<GammaMarket>)>>,
+}
+
+impl TradeFilter {
+    pub fn new(global: &MarketFilter, targets: &[TargetConfig], gamma_api_url: Option<String>) -> Result<Self> {
+        Ok(Self {
+            global: CompiledFilter::new(global)?,
+            targets: targets
+                .iter()
+                .map(|t| Ok((t.wallet.to_lowercase(), CompiledFilter::new(&t.markets)?)))
+                .collect::<Result<_>>()?,
+            gamma_api_url,
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// `Err` is the scope (`markets` or the target's name) and the rule that rejected `trade`.
+    pub async fn check(&self, trade: &TradePayload, target: &TargetConfig) -> std::result::Result<(), String> {
+        if trade.side.eq_ignore_ascii_case("SELL") {
+            return Ok(());
+        }
+        let filters = self.filters(target);
+        let market = match (&self.gamma_api_url, trade.condition_id()) {
+            (Some(url), Some(condition_id)) if self.needs_metadata(target) => match self.market(url, condition_id).await {
+                Some(market) => Some(market),
+                None => return Err("market metadata unavailable".to_string()),
+            },
+            _ => None,
+        };
+        for (scope, filter) in filters {
+            if let Some(filter) = filter {
+                filter.check(trade, market.as_ref()).map_err(|rule| format!("{} {}", scope, rule))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the global or `target`'s filter has tag or end-date rules.
+    pub fn needs_metadata(&self, target: &TargetConfig) -> bool {
+        self.filters(target).iter().any(|(_, f)| f.is_some_and(|f| f.needs_metadata()))
+    }
+
+    fn filters<'a>(&'a self, target: &'a TargetConfig) -> [(&'a str, Option<&'a CompiledFilter>); 2] {
+        [
+            ("markets", Some(&self.global)),
+            (target.name(), self.targets.get(&target.wallet.to_lowercase())),
+        ]
+    }
+
+    /// [`Self::check`], logging the rule that skipped the trade.
+    pub async fn allows(&self, trade: &TradePayload, target: &TargetConfig) -> bool {
+        match self.check(trade, target).await {
+            Ok(()) => true,
+            Err(rule) => {
+                info!(
+                    "Skipping {} from {} in {}: filtered by {}",
+                    trade.side,
+                    target.name(),
+                    trade.slug.as_deref().or(trade.condition_id()).unwrap_or(""),
+                    rule
+                );
+                false
+            }
+        }
+    }
+
+    async fn market(&self, gamma_api_url: &str, condition_id: &str) -> Option<GammaMarket> {
+        if let Some((fetched, market)) = self.cache.lock().unwrap().get(condition_id) {
+            if fetched.elapsed() < METADATA_TTL {
+                return market.clone();
+            }
+        }
+        match get_gamma_market(gamma_api_url, condition_id).await {
+            Ok(market) => {
+                self.cache
+                    .lock()
+                    .unwrap()
+                    .insert(condition_id.to_string(), (Instant::now(), market.clone()));
+                market
+            }
+            Err(e) => {
+                warn!("Could not fetch Gamma market {}: {}", condition_id, e);
+                None
+            }
+        }
+    }
+}
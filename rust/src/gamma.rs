@@ -0,0 +1,59 @@
+use crate::clob::client::http_client;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GammaTag {
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub slug: Option<String>,
+}
+
+/// Market metadata from the Gamma API `/markets` endpoint.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GammaMarket {
+    #[serde(default)]
+    pub condition_id: Option<String>,
+    #[serde(default)]
+    pub slug: Option<String>,
+    /// ISO 8601, e.g. `2024-11-05T12:00:00Z`.
+    #[serde(default)]
+    pub end_date: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<GammaTag>,
+}
+
+impl GammaMarket {
+    pub fn end_time(&self) -> Option<DateTime<Utc>> {
+        let raw = self.end_date.as_deref()?;
+        DateTime::parse_from_rfc3339(raw).ok().map(|t| t.with_timezone(&Utc))
+    }
+
+    /// Whether any tag's label or slug equals `name`, ignoring case.
+    pub fn has_tag(&self, name: &str) -> bool {
+        self.tags.iter().any(|t| {
+            t.label.as_deref().is_some_and(|l| l.eq_ignore_ascii_case(name))
+                || t.slug.as_deref().is_some_and(|s| s.eq_ignore_ascii_case(name))
+        })
+    }
+}
+
+/// Looks up one market by condition id, with its tags; `None` if Gamma does not know it.
+pub async fn get_gamma_market(gamma_api_url: &str, condition_id: &str) -> Result<Option<GammaMarket>> {
+    let url = format!(
+        "{}/markets?condition_ids={}&include_tag=true",
+        gamma_api_url.trim_end_matches('/'),
+        condition_id
+    );
+    let res = http_client().get(&url).send().await.context("gamma markets request")?;
+    let status = res.status();
+    if !status.is_success() {
+        let body = res.text().await.unwrap_or_default();
+        anyhow::bail!("Failed to fetch Gamma market: {} {}", status, body);
+    }
+    let markets: Vec<GammaMarket> = res.json().await.context("parse gamma markets response")?;
+    Ok(markets.into_iter().next())
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tracing::{error, info, warn};
 
 pub type TokenHoldings = HashMap<String, HashMap<String, f64>>;
@@ -81,3 +82,170 @@ pub fn clear_market_holdings(path: &Path, market_id: &str) -> Result<()> {
     }
     Ok(())
 }
+
+/// Per-target attribution of copied positions, kept next to the holdings file.
+pub type TargetLedgers = HashMap<String, TargetLedger>;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TargetLedger {
+    /// market id -> token id -> position copied from this target
+    #[serde(default)]
+    pub positions: HashMap<String, HashMap<String, TargetPosition>>,
+    /// USDC realized from sells and redemptions minus the cost of the shares they closed.
+    #[serde(default)]
+    pub realized_pnl: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct TargetPosition {
+    pub shares: f64,
+    /// USDC spent on the shares still held.
+    pub cost: f64,
+}
+
+/// `target-holdings.json` in the same directory as the holdings file.
+pub fn target_ledger_path(holdings_path: &Path) -> PathBuf {
+    holdings_path.with_file_name("target-holdings.json")
+}
+
+fn load_ledgers(path: &Path) -> TargetLedgers {
+    if !path.exists() {
+        return TargetLedgers::new();
+    }
+    match std::fs::read_to_string(path) {
+        Ok(s) => serde_json::from_str(&s).unwrap_or_else(|_| TargetLedgers::new()),
+        Err(e) => {
+            error!("Failed to load target ledger: {}", e);
+            TargetLedgers::new()
+        }
+    }
+}
+
+fn save_ledgers(path: &Path, ledgers: &TargetLedgers) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(ledgers)?)?;
+    Ok(())
+}
+
+pub fn get_target_ledgers(path: &Path) -> TargetLedgers {
+    load_ledgers(path)
+}
+
+/// Shares of `token_id` copied from `target` and not yet sold or redeemed.
+pub fn get_target_holdings(path: &Path, target: &str, market_id: &str, token_id: &str) -> f64 {
+    load_ledgers(path)
+        .get(&target.to_lowercase())
+        .and_then(|l| l.positions.get(market_id))
+        .and_then(|m| m.get(token_id))
+        .map(|p| p.shares)
+        .unwrap_or(0.0)
+}
+
+/// Attributes holdings of `token_id` that no target's ledger covers, i.e. positions from before
+/// per-target ledgers existed, to `target`. Their cost is unknown and recorded as zero. Returns
+/// the shares adopted.
+pub fn adopt_unledgered_holdings(
+    holdings_path: &Path,
+    ledger_path: &Path,
+    target: &str,
+    market_id: &str,
+    token_id: &str,
+) -> Result<f64> {
+    let held = get_holdings(holdings_path, market_id, token_id);
+    let ledgered = load_ledgers(ledger_path)
+        .values()
+        .any(|l| l.positions.get(market_id).is_some_and(|m| m.contains_key(token_id)));
+    if held <= 0.0 || ledgered {
+        return Ok(0.0);
+    }
+    record_target_buy(ledger_path, target, market_id, token_id, held, 0.0)?;
+    info!(
+        "Attributed {} unledgered shares of {} -> {} to target {}",
+        held,
+        market_id,
+        &token_id[..token_id.len().min(20)],
+        target
+    );
+    Ok(held)
+}
+
+pub fn record_target_buy(path: &Path, target: &str, market_id: &str, token_id: &str, shares: f64, cost: f64) -> Result<()> {
+    let mut ledgers = load_ledgers(path);
+    let position = ledgers
+        .entry(target.to_lowercase())
+        .or_default()
+        .positions
+        .entry(market_id.to_string())
+        .or_default()
+        .entry(token_id.to_string())
+        .or_default();
+    position.shares += shares;
+    position.cost += cost;
+    save_ledgers(path, &ledgers)
+}
+
+/// Closes `shares` of a target's position for `proceeds` USDC and returns the realized PnL.
+pub fn record_target_sell(
+    path: &Path,
+    target: &str,
+    market_id: &str,
+    token_id: &str,
+    shares: f64,
+    proceeds: f64,
+) -> Result<f64> {
+    let mut ledgers = load_ledgers(path);
+    let ledger = ledgers.entry(target.to_lowercase()).or_default();
+    let mut cost_closed = 0.0;
+    if let Some(tokens) = ledger.positions.get_mut(market_id) {
+        if let Some(position) = tokens.get_mut(token_id) {
+            let fraction = if position.shares > 0.0 { (shares / position.shares).min(1.0) } else { 1.0 };
+            cost_closed = position.cost * fraction;
+            position.shares -= shares;
+            position.cost -= cost_closed;
+            if position.shares <= 0.0 {
+                tokens.remove(token_id);
+            }
+        }
+        if tokens.is_empty() {
+            ledger.positions.remove(market_id);
+        }
+    }
+    let pnl = proceeds - cost_closed;
+    ledger.realized_pnl += pnl;
+    save_ledgers(path, &ledgers)?;
+    Ok(pnl)
+}
+
+/// Closes every target's position in a redeemed market. `payout` is split between holders of
+/// the winning tokens by their shares of those tokens; losing tokens are worth nothing.
+pub fn settle_target_market(path: &Path, market_id: &str, payout: f64, winning_token_ids: &[String]) -> Result<()> {
+    let mut ledgers = load_ledgers(path);
+    let winning_shares = |l: &TargetLedger| -> f64 {
+        l.positions
+            .get(market_id)
+            .map(|m| m.iter().filter(|(t, _)| winning_token_ids.contains(t)).map(|(_, p)| p.shares).sum())
+            .unwrap_or(0.0)
+    };
+    let total_winning: f64 = ledgers.values().map(winning_shares).sum();
+    if total_winning <= 0.0 && payout > 0.0 && ledgers.values().any(|l| l.positions.contains_key(market_id)) {
+        warn!("No target holds a winning token of {}; {:.6} USDC payout is not attributed", market_id, payout);
+    }
+    let mut settled = false;
+    for (target, ledger) in ledgers.iter_mut() {
+        let shares = winning_shares(ledger);
+        if let Some(tokens) = ledger.positions.remove(market_id) {
+            let cost: f64 = tokens.values().map(|p| p.cost).sum();
+            let proceeds = if total_winning > 0.0 { payout * shares / total_winning } else { 0.0 };
+            let pnl = proceeds - cost;
+            ledger.realized_pnl += pnl;
+            settled = true;
+            info!("Settled {} for target {}: realized PnL {:.6} USDC", market_id, target, pnl);
+        }
+    }
+    if !settled {
+        return Ok(());
+    }
+    save_ledgers(path, &ledgers)
+}
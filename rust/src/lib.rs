@@ -1,17 +1,32 @@
+pub mod backtest;
 pub mod balance;
 pub mod chain;
 pub mod clob;
 pub mod config;
+pub mod data_api;
 pub mod feed;
+pub mod filter;
+pub mod gamma;
 pub mod holdings;
 pub mod order;
 pub mod redemption;
+pub mod risk;
+pub mod stale;
 
 pub use balance::{display_wallet_balance, validate_buy_order_balance};
 pub use chain::{approve_tokens_after_buy, approve_usdc_allowance, get_contract_config};
-pub use config::Config;
+pub use config::{Config, MarketFilter, SellMode, Sizing, TargetConfig};
 pub use clob::{create_or_load_credential, wallet_address, ClobClient};
-pub use feed::{run_feed, TradePayload};
-pub use holdings::{add_holdings, clear_market_holdings, get_all_holdings, get_holdings, remove_holdings};
-pub use order::{CopyTradeResult, TradeOrderBuilder};
-pub use redemption::{auto_redeem_resolved_markets, redeem_market, redeem_positions};
+pub use feed::{run_feed, trade_latency, BackfillConfig, FeedStats, TradePayload};
+pub use filter::TradeFilter;
+pub use holdings::{
+    add_holdings, adopt_unledgered_holdings, clear_market_holdings, get_all_holdings, get_holdings, get_target_holdings, get_target_ledgers,
+    record_target_buy, record_target_sell, remove_holdings, settle_target_market, target_ledger_path,
+};
+pub use order::{CopyTradeResult, PaperFillModel, TradeOrderBuilder};
+pub use redemption::{
+    auto_redeem_markets, auto_redeem_resolved_markets, plan_redemptions, redeem_market, redeem_positions, RedeemConfig,
+    RedeemOutcome, RedeemPlan, RedeemRoute,
+};
+pub use risk::{RiskManager, RiskViolation};
+pub use stale::StaleTradeGuard;
@@ -1,20 +1,29 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use polymarket_copy_bot::{
-    auto_redeem_resolved_markets, create_or_load_credential, run_feed, ClobClient, Config,
-    TradeOrderBuilder,
+    auto_redeem_markets, auto_redeem_resolved_markets, create_or_load_credential, plan_redemptions, run_feed,
+    BackfillConfig, ClobClient, Config, FeedStats, PaperFillModel, RedeemConfig, RiskManager, StaleTradeGuard,
+    TradeFilter, TradeOrderBuilder,
 };
-use polymarket_copy_bot::{approve_usdc_allowance, display_wallet_balance};
+use polymarket_copy_bot::backtest::{load_trades_jsonl, run_backtest_comparison, BacktestDataset};
+use polymarket_copy_bot::{approve_usdc_allowance, display_wallet_balance, TargetConfig};
+use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
-use std::time::Duration;
-use tracing::{error, info};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{error, info, warn};
 use tracing_subscriber::EnvFilter;
 
+const FEED_HEALTH_LOG_INTERVAL: Duration = Duration::from_secs(600);
+const RESTING_ORDER_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
 #[derive(Parser)]
 #[command(name = "polymarket-copy-bot")]
 #[command(about = "Polymarket copy trading bot")]
 struct Cli {
+    /// TOML config file; environment variables override its values
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -22,7 +31,11 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Run the copy-trading bot
-    Bot,
+    Bot {
+        /// Simulate fills against the live order book instead of posting orders
+        #[arg(long)]
+        paper: bool,
+    },
     /// Redeem positions for a condition ID
     Redeem {
         #[arg(required = true)]
@@ -32,11 +45,58 @@ enum Commands {
     },
     /// Auto-redeem all resolved markets from holdings
     AutoRedeem {
+        /// Print the redemption plan without sending transactions
         #[arg(long)]
         dry_run: bool,
+        /// Discover positions from the Data API instead of token-holding.json
         #[arg(long)]
         api: bool,
     },
+    /// Replay a target wallet's trade history through the copy pipeline
+    Backtest {
+        /// Target wallet (default: the first enabled target)
+        #[arg(long)]
+        target: Option<String>,
+        /// Days of history to fetch from the Data API
+        #[arg(long, default_value_t = 30)]
+        days: u64,
+        /// Load trades from a JSONL file of trade payloads instead of the Data API
+        #[arg(long)]
+        trades: Option<PathBuf>,
+        /// Dataset directory with trades.jsonl and resolutions.json (default: src/data/backtest/<wallet>)
+        #[arg(long)]
+        dataset: Option<PathBuf>,
+        /// Use only the cached dataset, without any network access
+        #[arg(long)]
+        offline: bool,
+        /// Simulated starting USDC balance (default: paper.starting_balance)
+        #[arg(long)]
+        balance: Option<f64>,
+    },
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+    /// Manage open CLOB orders
+    Orders {
+        #[command(subcommand)]
+        action: OrdersCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Validate the configuration and print it with secrets redacted
+    Check,
+}
+
+#[derive(Subcommand)]
+enum OrdersCommand {
+    /// Cancel one order by id
+    Cancel { order_id: String },
+    /// Cancel all open orders
+    CancelAll,
 }
 
 #[tokio::main]
@@ -46,75 +106,144 @@ async fn main() -> Result<()> {
     tracing_subscriber::fmt().with_env_filter(filter).init();
 
     let cli = Cli::parse();
+    let config = Config::load(cli.config.as_deref())?;
     match cli.command {
-        Commands::Bot => run_bot().await,
-        Commands::Redeem { condition_id, index_sets } => run_redeem(condition_id, index_sets).await,
-        Commands::AutoRedeem { dry_run, api } => run_auto_redeem(dry_run, api).await,
+        Commands::Bot { paper } => {
+            let mut config = config;
+            config.paper.enabled |= paper;
+            run_bot(config).await
+        }
+        Commands::Redeem { condition_id, index_sets } => run_redeem(config, condition_id, index_sets).await,
+        Commands::AutoRedeem { dry_run, api } => run_auto_redeem(config, dry_run, api).await,
+        Commands::Backtest { target, days, trades, dataset, offline, balance } => {
+            run_backtest(config, target, days, trades, dataset, offline, balance).await
+        }
+        Commands::Config { action: ConfigCommand::Check } => {
+            print!("{}", config.to_redacted_toml()?);
+            Ok(())
+        }
+        Commands::Orders { action } => run_orders(config, action).await,
     }
 }
 
-async fn run_bot() -> Result<()> {
-    let config = Config::from_env()?;
+async fn run_bot(config: Config) -> Result<()> {
     info!("Starting the bot...");
-    info!("Target Wallet: {}", config.target_wallet);
-    info!("Size Multiplier: {}x", config.size_multiplier);
+    for t in &config.targets {
+        info!(
+            "Target {} ({}): multiplier={}x max_order={} enabled={}",
+            t.name(),
+            t.wallet,
+            t.size_multiplier,
+            t.max_order_amount.map(|m| m.to_string()).unwrap_or_else(|| "none".to_string()),
+            t.enabled
+        );
+    }
+    let target_wallets = config.enabled_target_wallets();
+    if target_wallets.is_empty() {
+        anyhow::bail!("No enabled targets configured");
+    }
     info!("Copy Trading: {}", if config.enable_copy_trading { "enabled" } else { "disabled" });
+    if config.paper.enabled {
+        info!(
+            "PAPER TRADING: no orders are posted; holdings in {}",
+            config.paper.holdings_path.display()
+        );
+    }
 
-    let creds = create_or_load_credential(
-        &config.clob_api_url,
-        config.chain_id,
-        &config.private_key,
-        &config.credential_path,
-    )
-    .await?;
-    let creds = match creds {
+    let clob = match clob_client(&config).await? {
         Some(c) => c,
-        None => {
-            error!("No credentials - run once with TS bot to create credential.json or implement L1 create");
-            return Ok(());
-        }
+        None => return Ok(()),
     };
 
-    let wallet_addr = polymarket_copy_bot::wallet_address(&config.private_key)?;
-    let clob = ClobClient::new(
-        config.clob_api_url.clone(),
-        creds.clone(),
-        wallet_addr,
-        2, // GNOSIS_SAFE
-    );
+    let feed_stats = Arc::new(FeedStats::default());
+    let stale_guard = Arc::new(StaleTradeGuard::new(config.stale, config.clob_api_url.clone()));
+    {
+        let feed_stats = feed_stats.clone();
+        let stale_guard = stale_guard.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FEED_HEALTH_LOG_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                info!(
+                    "Feed health: connected={} reconnects={} total_downtime={:?} trades={} latency avg={:?} max={:?} last={:?} stale_skipped={}",
+                    feed_stats.is_connected(),
+                    feed_stats.reconnects(),
+                    feed_stats.total_downtime(),
+                    feed_stats.trades(),
+                    feed_stats.avg_latency(),
+                    feed_stats.max_latency(),
+                    feed_stats.last_latency(),
+                    stale_guard.skipped()
+                );
+            }
+        });
+    }
 
     if config.enable_copy_trading {
         let provider = ethers::prelude::Provider::<ethers::prelude::Http>::try_from(&config.rpc_url)?;
         let wallet = ethers::signers::LocalWallet::from_bytes(
             &hex::decode(config.private_key.trim_start_matches("0x"))?,
         )?;
-        approve_usdc_allowance(&provider, &wallet, config.chain_id, config.neg_risk).await?;
-        clob.update_balance_allowance("COLLATERAL").await?;
-        display_wallet_balance(&clob).await?;
+        if !config.paper.enabled {
+            approve_usdc_allowance(&provider, &wallet, config.chain_id, config.neg_risk).await?;
+            clob.update_balance_allowance("COLLATERAL").await?;
+            display_wallet_balance(&clob).await?;
+        }
 
-        let order_builder = Arc::new(TradeOrderBuilder::new(
+        let mut order_builder = TradeOrderBuilder::new(
             clob.clone(),
             provider,
             wallet,
             config.chain_id,
-            config.holdings_path.clone(),
-            config.tick_size.as_str().to_string(),
-            config.neg_risk,
-            if config.order_type == polymarket_copy_bot::config::OrderType::FOK {
-                "FOK".to_string()
+            if config.paper.enabled {
+                config.paper.holdings_path.clone()
             } else {
-                "FAK".to_string()
+                config.holdings_path.clone()
             },
-        ));
+            config.tick_size.as_str().to_string(),
+            config.neg_risk,
+            config.order_type.as_str().to_string(),
+        )
+        .with_data_api(config.data_api_url.clone())
+        .with_risk(config.risk.clone())
+        .with_slippage(config.slippage)
+        .with_order_timeout(config.order_timeout_secs);
+        if config.paper.enabled {
+            order_builder = order_builder.with_paper_trading(config.paper.starting_balance, PaperFillModel::OrderBook);
+            if let Some(account) = order_builder.paper_account() {
+                info!(
+                    "Paper account: balance={:.6} USDC (started with {:.6}), realized PnL={:.6}, fills={}",
+                    account.usdc_balance, account.starting_balance, account.realized_pnl, account.fills
+                );
+            }
+        }
+        let order_builder = Arc::new(order_builder);
+        // Also reconciles market orders whose fill could not be confirmed when posted.
+        if !order_builder.is_paper() {
+            let ob = order_builder.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(RESTING_ORDER_POLL_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = ob.sync_resting_orders().await {
+                        error!("Resting order check failed: {:#}", e);
+                    }
+                }
+            });
+        }
 
         let copy_paused = Arc::new(AtomicBool::new(false));
-        let redeem_duration = config.redeem_duration_minutes;
+        let redeem_duration = config.redemption.interval_minutes;
+        let redeem_retries = config.redemption.max_retries;
         let holdings_path = config.holdings_path.clone();
-        let chain_id = config.chain_id;
-        let private_key = config.private_key.clone();
-        let rpc_url = config.rpc_url.clone();
+        let redeem_config = RedeemConfig::from_config(&config);
+        let redeem_risk = RiskManager::new(config.risk.clone(), &config.holdings_path);
+        let ledger_lock = order_builder.ledger_lock();
 
-        if let Some(mins) = redeem_duration {
+        if config.paper.enabled && redeem_duration.is_some() {
+            info!("Auto-redeem loop disabled in paper mode");
+        } else if let Some(mins) = redeem_duration {
             let copy_paused_clone = copy_paused.clone();
             let interval = Duration::from_secs(mins * 60);
             tokio::spawn(async move {
@@ -125,14 +254,15 @@ async fn run_bot() -> Result<()> {
                     info!("Copy trading PAUSED for redemption");
                     let summary = auto_redeem_resolved_markets(
                         &holdings_path,
-                        chain_id,
-                        &private_key,
-                        &rpc_url,
-                        3,
+                        &redeem_config,
+                        redeem_retries,
+                        &redeem_risk,
+                        &ledger_lock,
                     )
                     .await;
-                    if let Ok(s) = summary {
-                        info!("Redemption: total={} resolved={} redeemed={} failed={}", s.total, s.resolved, s.redeemed, s.failed);
+                    match summary {
+                        Ok(s) => info!("Redemption: total={} resolved={} redeemed={} failed={}", s.total, s.resolved, s.redeemed, s.failed),
+                        Err(e) => error!("Redemption run failed: {:#}", e),
                     }
                     copy_paused_clone.store(false, std::sync::atomic::Ordering::SeqCst);
                     info!("Copy trading RESUMED");
@@ -140,30 +270,67 @@ async fn run_bot() -> Result<()> {
             });
         }
 
-        let target = config.target_wallet.clone();
-        let size_mult = config.size_multiplier;
-        let max_amt = config.max_order_amount;
+        let targets: Arc<Vec<_>> = Arc::new(
+            config
+                .targets
+                .iter()
+                .map(|t| t.with_max_order_cap(config.risk.max_order_amount))
+                .collect(),
+        );
+        let filter = Arc::new(TradeFilter::new(&config.markets, &targets, Some(config.gamma_api_url.clone()))?);
+        let shutdown_builder = order_builder.clone();
         let order_builder = order_builder.clone();
-        run_feed(
+        let backfill = BackfillConfig {
+            data_api_url: config.data_api_url.clone(),
+            max_age: Duration::from_secs(config.backfill_max_age_secs),
+        };
+        let feed = run_feed(
             &config.ws_url,
-            &target,
+            &target_wallets,
             copy_paused.as_ref(),
             config.enable_copy_trading,
+            &feed_stats,
+            Some(&backfill),
             move |trade| {
                 let ob = order_builder.clone();
+                let targets = targets.clone();
+                let filter = filter.clone();
+                let stale_guard = stale_guard.clone();
                 async move {
-                    ob.copy_trade(&trade, size_mult, max_amt).await?;
+                    let trader = trade.proxy_wallet.as_deref().unwrap_or("");
+                    let target = match targets.iter().find(|t| t.enabled && t.is_wallet(trader)) {
+                        Some(t) => t,
+                        None => return Ok(()),
+                    };
+                    // Skipped trades still move the target's position, which later SELLs are sized against.
+                    let sold_fraction = ob.record_target_trade(&trade, target).await;
+                    if !filter.allows(&trade, target).await || !stale_guard.allows(&trade, target.name()).await {
+                        return Ok(());
+                    }
+                    ob.copy_recorded_trade(&trade, target, sold_fraction).await?;
                     Ok(())
                 }
             },
-        )
-        .await?;
+        );
+        tokio::select! {
+            res = feed => res?,
+            signal = shutdown_signal() => {
+                info!("{} received, shutting down", signal);
+                if shutdown_builder.rests_orders() {
+                    if let Err(e) = shutdown_builder.cancel_all_resting("Shutting down").await {
+                        error!("Cancelling resting orders failed: {:#}", e);
+                    }
+                }
+            }
+        }
     } else {
         run_feed(
             &config.ws_url,
-            &config.target_wallet,
+            &target_wallets,
             &AtomicBool::new(false),
             false,
+            &feed_stats,
+            None,
             |_| async { Ok(()) },
         )
         .await?;
@@ -171,29 +338,236 @@ async fn run_bot() -> Result<()> {
     Ok(())
 }
 
-async fn run_redeem(condition_id: String, index_sets: Vec<u64>) -> Result<()> {
-    let config = Config::from_env()?;
-    let sets = if index_sets.is_empty() { vec![1, 2] } else { index_sets };
-    polymarket_copy_bot::redeem_positions(
-        &condition_id,
-        Some(sets),
+/// An authenticated CLOB client for the configured wallet, or `None` if there are no API
+/// credentials yet.
+async fn clob_client(config: &Config) -> Result<Option<ClobClient>> {
+    let creds = create_or_load_credential(
+        &config.clob_api_url,
         config.chain_id,
         &config.private_key,
-        &config.rpc_url,
+        &config.credential_path,
+    )
+    .await?;
+    let creds = match creds {
+        Some(c) => c,
+        None => {
+            error!("No credentials - run once with TS bot to create credential.json or implement L1 create");
+            return Ok(None);
+        }
+    };
+
+    let wallet_addr = polymarket_copy_bot::wallet_address(&config.private_key)?;
+    Ok(Some(ClobClient::new(
+        config.clob_api_url.clone(),
+        creds,
+        wallet_addr,
+        config.signature_type,
     )
-    .await
+    .with_funder(config.funder_address.clone())))
+}
+
+async fn run_orders(config: Config, action: OrdersCommand) -> Result<()> {
+    let clob = match clob_client(&config).await? {
+        Some(c) => c,
+        None => return Ok(()),
+    };
+    match action {
+        OrdersCommand::Cancel { order_id } => {
+            clob.cancel_order(&order_id).await?;
+            info!("Cancelled order {}", order_id);
+        }
+        OrdersCommand::CancelAll => {
+            let out = clob.cancel_all().await?;
+            info!("Cancelled {} order(s)", out.canceled.len());
+            for id in &out.canceled {
+                info!("  {}", id);
+            }
+            for (id, reason) in &out.not_canceled {
+                info!("Not cancelled {}: {}", id, reason);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_redeem(config: Config, condition_id: String, index_sets: Vec<u64>) -> Result<()> {
+    let sets = if index_sets.is_empty() { vec![1, 2] } else { index_sets };
+    let outcome =
+        polymarket_copy_bot::redeem_positions(&condition_id, Some(sets), &RedeemConfig::from_config(&config)).await?;
+    info!(
+        "Redeemed {} index sets {:?}: tx={} USDC received={:.6}",
+        outcome.condition_id, outcome.index_sets, outcome.tx_hash, outcome.usdc_received
+    );
+    Ok(())
 }
 
-async fn run_auto_redeem(_dry_run: bool, _api: bool) -> Result<()> {
-    let config = Config::from_env()?;
-    let summary = auto_redeem_resolved_markets(
+/// Waits for Ctrl-C or, on Unix, SIGTERM (sent by `docker stop`, systemd and Kubernetes);
+/// returns the signal's name.
+async fn shutdown_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => "Ctrl-C",
+                    _ = terminate.recv() => "SIGTERM",
+                }
+            }
+            Err(e) => {
+                error!("Could not listen for SIGTERM: {}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                "Ctrl-C"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "Ctrl-C"
+    }
+}
+
+async fn run_auto_redeem(config: Config, dry_run: bool, api: bool) -> Result<()> {
+    let redeem_config = RedeemConfig::from_config(&config);
+    let condition_ids: Vec<String> = if api {
+        let user = polymarket_copy_bot::wallet_address(&config.private_key)?;
+        let positions = polymarket_copy_bot::data_api::get_positions(&config.data_api_url, &user).await?;
+        let mut ids: Vec<String> = positions.into_iter().map(|p| p.condition_id).collect();
+        ids.sort();
+        ids.dedup();
+        info!("Found {} market(s) with current positions via Data API", ids.len());
+        ids
+    } else {
+        polymarket_copy_bot::get_all_holdings(&config.holdings_path).into_keys().collect()
+    };
+
+    if dry_run {
+        let plans = plan_redemptions(&condition_ids, &redeem_config).await?;
+        info!("=== DRY RUN: redemption plan ({} markets) ===", plans.len());
+        let mut total_payout = 0.0;
+        for plan in &plans {
+            if let Some(e) = &plan.error {
+                info!("{}: error: {}", plan.condition_id, e);
+            } else if !plan.is_resolved {
+                info!("{}: not resolved", plan.condition_id);
+            } else {
+                total_payout += plan.expected_payout;
+                info!(
+                    "{} [{:?}]: winning index sets {:?} (held {:?}), expected payout {:.6} USDC, estimated gas {} (~{:.6} POL)",
+                    plan.condition_id,
+                    plan.route,
+                    plan.winning_index_sets,
+                    plan.held_winning_index_sets,
+                    plan.expected_payout,
+                    plan.estimated_gas.map(|g| g.to_string()).unwrap_or_else(|| "n/a".to_string()),
+                    plan.estimated_cost().unwrap_or(0.0)
+                );
+            }
+        }
+        info!("Expected total payout: {:.6} USDC (no transactions sent)", total_payout);
+        return Ok(());
+    }
+
+    let risk = RiskManager::new(config.risk.clone(), &config.holdings_path);
+    let summary = auto_redeem_markets(
+        &condition_ids,
         &config.holdings_path,
-        config.chain_id,
-        &config.private_key,
-        &config.rpc_url,
-        3,
+        &redeem_config,
+        config.redemption.max_retries,
+        &risk,
+        &Mutex::new(()),
     )
     .await?;
-    info!("Total: {} Resolved: {} Redeemed: {} Failed: {}", summary.total, summary.resolved, summary.redeemed, summary.failed);
+    for r in &summary.results {
+        if r.redeemed {
+            info!(
+                "{}: redeemed tx={} USDC={:.6}",
+                r.condition_id,
+                r.tx_hash.as_deref().unwrap_or(""),
+                r.usdc_received.unwrap_or(0.0)
+            );
+        } else if let Some(e) = &r.error {
+            info!("{}: {}", r.condition_id, e);
+        }
+    }
+    Ok(())
+}
+
+async fn run_backtest(
+    config: Config,
+    target: Option<String>,
+    days: u64,
+    trades: Option<PathBuf>,
+    dataset_dir: Option<PathBuf>,
+    offline: bool,
+    balance: Option<f64>,
+) -> Result<()> {
+    let target = match target {
+        Some(wallet) => config
+            .targets
+            .iter()
+            .find(|t| t.is_wallet(&wallet))
+            .cloned()
+            .unwrap_or_else(|| TargetConfig::new(wallet)),
+        None => match config.targets.iter().find(|t| t.enabled) {
+            Some(t) => t.clone(),
+            None => anyhow::bail!("No target to backtest; pass --target"),
+        },
+    }
+    .with_max_order_cap(config.risk.max_order_amount);
+    let dataset_dir = dataset_dir.unwrap_or_else(|| {
+        config
+            .holdings_path
+            .with_file_name("backtest")
+            .join(target.wallet.to_lowercase())
+    });
+
+    let mut dataset = match (&trades, offline) {
+        (Some(path), _) => BacktestDataset {
+            trades: load_trades_jsonl(path)?,
+            resolutions: BacktestDataset::load(&dataset_dir).map(|d| d.resolutions).unwrap_or_default(),
+        },
+        (None, true) => BacktestDataset::load(&dataset_dir)?,
+        (None, false) => {
+            let since = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs().saturating_sub(days * 86_400);
+            let mut d = BacktestDataset::fetch_trades(&config.data_api_url, &target.wallet, since).await?;
+            d.resolutions = BacktestDataset::load(&dataset_dir).map(|d| d.resolutions).unwrap_or_default();
+            d
+        }
+    };
+    if !offline {
+        dataset.fetch_resolutions(&config.clob_api_url).await;
+        dataset.save(&dataset_dir)?;
+        info!("Dataset cached in {} (rerun with --offline)", dataset_dir.display());
+    }
+
+    let balance = balance.unwrap_or(config.paper.starting_balance);
+    let cmp = run_backtest_comparison(&dataset, &target, &config.markets, balance, &dataset_dir.join("run")).await?;
+    let r = &cmp.capped;
+    info!("=== BACKTEST: {} ({} trades) ===", target.name(), r.trades_seen);
+    info!("Copied {} / skipped {}", r.trades_copied, r.trades_skipped);
+    if r.metadata_rules_ignored {
+        warn!("Tag and min_hours_to_end market filters were not applied: they need Gamma, which backtests do not query");
+    }
+    info!("Hit rate: {:.1}% ({} wins / {} losses)", r.hit_rate() * 100.0, r.wins, r.losses);
+    info!(
+        "PnL: {:.2} USDC, ROI: {:.2}% (start {:.2}, end equity {:.2})",
+        r.pnl(),
+        r.roi() * 100.0,
+        r.starting_balance,
+        r.final_equity
+    );
+    info!("Max drawdown: {:.2}%", r.max_drawdown * 100.0);
+    info!("Turnover: {:.2} USDC ({:.2}x starting balance)", r.turnover, r.turnover / balance.max(f64::EPSILON));
+    info!("Open positions in unresolved markets: {} (marked at {:.2} USDC)", r.open_positions, r.open_value);
+    info!(
+        "Order cap {}: clipped {} buy(s); uncapped PnL {:.2} USDC, ROI {:.2}% (cap changed PnL by {:+.2})",
+        target.max_order_amount.map(|m| m.to_string()).unwrap_or_else(|| "none".to_string()),
+        cmp.clipped_orders,
+        cmp.uncapped.pnl(),
+        cmp.uncapped.roi() * 100.0,
+        r.pnl() - cmp.uncapped.pnl()
+    );
     Ok(())
 }
@@ -0,0 +1,47 @@
+use super::OrderSide;
+use crate::clob::ClobTrade;
+
+/// What an order actually filled.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Fill {
+    pub shares: f64,
+    pub usdc: f64,
+}
+
+impl Fill {
+    pub fn avg_price(&self) -> Option<f64> {
+        (self.shares > 0.0).then(|| self.usdc / self.shares)
+    }
+}
+
+/// The fill reported by `makingAmount`/`takingAmount` of an order response: BUYs make USDC
+/// and take shares, SELLs the reverse. `None` unless both amounts are positive.
+pub fn fill_from_response(side: OrderSide, making: Option<f64>, taking: Option<f64>) -> Option<Fill> {
+    let (making, taking) = (making.filter(|a| *a > 0.0)?, taking.filter(|a| *a > 0.0)?);
+    Some(match side {
+        OrderSide::Buy => Fill { shares: taking, usdc: making },
+        OrderSide::Sell => Fill { shares: making, usdc: taking },
+    })
+}
+
+/// What `order_id` filled in `trades`: matches it took as taker at the trade price, plus
+/// matches it made at its own price.
+pub fn fill_from_trades(order_id: &str, trades: &[ClobTrade]) -> Fill {
+    let mut fill = Fill::default();
+    for trade in trades {
+        if trade.taker_order_id.as_deref() == Some(order_id) {
+            if let (Ok(size), Ok(price)) = (trade.size.parse::<f64>(), trade.price.parse::<f64>()) {
+                fill.shares += size;
+                fill.usdc += size * price;
+            }
+            continue;
+        }
+        for maker in trade.maker_orders.iter().filter(|m| m.order_id == order_id) {
+            if let (Ok(size), Ok(price)) = (maker.matched_amount.parse::<f64>(), maker.price.parse::<f64>()) {
+                fill.shares += size;
+                fill.usdc += size * price;
+            }
+        }
+    }
+    fill
+}
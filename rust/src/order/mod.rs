@@ -1,18 +1,100 @@
-use crate::balance::{display_wallet_balance, validate_buy_order_balance};
-use crate::chain::approve_tokens_after_buy;
-use crate::clob::ClobClient;
+use crate::balance::{
+    display_wallet_balance, get_available_balance, get_reserved, validate_buy_order_balance, BalanceCheck,
+};
+use crate::chain::{address_from_hex, approve_tokens_after_buy, usdc_balance};
+use crate::clob::{get_order_book, ClobClient, PostOrderResponse};
+use crate::config::{RiskConfig, SlippageConfig, TargetConfig};
+use crate::data_api::get_portfolio_value;
 use crate::feed::TradePayload;
-use crate::holdings::{add_holdings, get_holdings, remove_holdings};
+use crate::risk::{RiskManager, RiskViolation};
+use crate::holdings::{
+    add_holdings, adopt_unledgered_holdings, get_holdings, get_target_holdings, record_target_buy, record_target_sell, remove_holdings,
+    target_ledger_path,
+};
 use anyhow::Result;
+use chrono::Utc;
 use ethers::prelude::*;
 use tracing::{info, warn};
 
-#[derive(Debug)]
+mod fills;
+mod paper;
+mod resting;
+mod signing;
+mod sizing;
+mod slippage;
+mod target_positions;
+
+pub use fills::{fill_from_response, fill_from_trades, Fill};
+pub use paper::{paper_account_path, simulate_market_fill, PaperAccount, SimulatedFill};
+pub use resting::{load_resting_orders, resting_orders_path, save_resting_orders, RestingOrder};
+pub use signing::{
+    exchange_domain_separator, generate_salt, limit_order_amounts, market_order_amounts, order_hash, round_config,
+    sign_order, Order, OrderSide, RoundConfig, SignedOrder,
+};
+pub use sizing::{
+    sizing_strategy, BalancePercentSizing, FixedUsdSizing, MultiplierSizing, PortfolioProportionalSizing, SizingInput,
+    SizingStrategy,
+};
+pub use slippage::{check_book, worst_price};
+pub use target_positions::{sell_amount, TargetPositions, MIN_ORDER_SHARES};
+
+/// GTD expirations must be at least this far ahead of the exchange's clock, on top of the
+/// time the order should live.
+const GTD_SECURITY_THRESHOLD_SECS: u64 = 60;
+
+/// How often, and how many times, a market order without fill amounts in its response is
+/// polled for its matched size.
+const FILL_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+const FILL_POLL_ATTEMPTS: usize = 5;
+
+#[derive(Debug, Default)]
 pub struct CopyTradeResult {
     pub success: bool,
     pub order_id: Option<String>,
     pub transaction_hashes: Option<Vec<String>>,
+    pub status: Option<String>,
+    pub making_amount: Option<f64>,
+    pub taking_amount: Option<f64>,
     pub error: Option<String>,
+    /// The risk limit that rejected the trade; `error` carries its description.
+    pub risk_violation: Option<RiskViolation>,
+}
+
+impl CopyTradeResult {
+    fn failure(error: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            error: Some(error.into()),
+            ..Default::default()
+        }
+    }
+
+    fn rejected(violation: RiskViolation) -> Self {
+        Self {
+            error: Some(violation.to_string()),
+            risk_violation: Some(violation),
+            ..Default::default()
+        }
+    }
+}
+
+/// Order parameters that differ per market.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketParams {
+    pub tick_size: String,
+    /// Orders are signed for the neg-risk exchange.
+    pub neg_risk: bool,
+}
+
+impl MarketParams {
+    fn tick(&self) -> f64 {
+        self.tick_size.parse().unwrap_or(0.01)
+    }
+}
+
+/// The target's price rounded to a tick on the side that is no worse for us.
+fn limit_price(side: OrderSide, trade_price: f64, params: &MarketParams) -> f64 {
+    worst_price(side, trade_price, &SlippageConfig::default(), params.tick())
 }
 
 pub struct TradeOrderBuilder {
@@ -21,12 +103,41 @@ pub struct TradeOrderBuilder {
     wallet: LocalWallet,
     chain_id: u64,
     holdings_path: std::path::PathBuf,
+    /// Used when the CLOB lookup of a token's tick size fails.
     tick_size: String,
+    /// Used when the CLOB lookup of a token's neg-risk flag fails.
     neg_risk: bool,
     order_type: String,
+    paper: Option<PaperSettings>,
+    data_api_url: Option<String>,
+    target_positions: TargetPositions,
+    risk: Option<RiskManager>,
+    slippage: SlippageConfig,
+    order_timeout_secs: u64,
+    /// Serializes updates of the resting orders file.
+    resting_lock: std::sync::Mutex<()>,
+    /// Serializes updates of the holdings file and target ledgers; shared with the redeem loop
+    /// through [`Self::ledger_lock`].
+    ledger_lock: std::sync::Arc<std::sync::Mutex<()>>,
+}
+
+struct PaperSettings {
+    account_path: std::path::PathBuf,
+    starting_balance: f64,
+    fill_model: PaperFillModel,
+}
+
+/// How simulated orders are filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaperFillModel {
+    /// Walk the live CLOB `/book` up to the order price.
+    OrderBook,
+    /// Fill the whole amount at the target's trade price; needs no network (backtests).
+    TradePrice,
 }
 
 impl TradeOrderBuilder {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         clob: ClobClient,
         provider: Provider<Http>,
@@ -46,106 +157,984 @@ impl TradeOrderBuilder {
             tick_size,
             neg_risk,
             order_type,
+            paper: None,
+            data_api_url: None,
+            target_positions: TargetPositions::default(),
+            risk: None,
+            slippage: SlippageConfig::default(),
+            order_timeout_secs: 300,
+            resting_lock: std::sync::Mutex::new(()),
+            ledger_lock: std::sync::Arc::new(std::sync::Mutex::new(())),
         }
     }
 
-    pub async fn copy_trade(
+    /// How long GTC/GTD orders may rest unfilled before they are cancelled.
+    pub fn with_order_timeout(mut self, secs: u64) -> Self {
+        self.order_timeout_secs = secs;
+        self
+    }
+
+    /// Limits how much worse than the target's price copied orders may fill.
+    pub fn with_slippage(mut self, slippage: SlippageConfig) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    /// Gates every copied BUY on `limits`, measured against the holdings file's target ledgers.
+    pub fn with_risk(mut self, limits: RiskConfig) -> Self {
+        self.risk = Some(RiskManager::new(limits, &self.holdings_path));
+        self
+    }
+
+    /// Lets proportional sells look up target positions the bot has not seen from the start,
+    /// and portfolio-proportional sizing look up portfolio values.
+    pub fn with_data_api(mut self, data_api_url: String) -> Self {
+        self.target_positions = TargetPositions::new(Some(data_api_url.clone()));
+        self.data_api_url = Some(data_api_url);
+        self
+    }
+
+    /// Paper mode: orders are simulated with `fill_model` instead of being posted, and the
+    /// simulated USDC balance is kept in `paper-account.json` next to the holdings file (which
+    /// should be a separate, paper-only file).
+    pub fn with_paper_trading(mut self, starting_balance: f64, fill_model: PaperFillModel) -> Self {
+        self.paper = Some(PaperSettings {
+            account_path: paper_account_path(&self.holdings_path),
+            starting_balance,
+            fill_model,
+        });
+        self
+    }
+
+    pub fn is_paper(&self) -> bool {
+        self.paper.is_some()
+    }
+
+    /// Whether copies are GTC/GTD orders resting on the book. Paper mode fills limit orders
+    /// only as far as the book allows at once, like FAK.
+    pub fn rests_orders(&self) -> bool {
+        matches!(self.order_type.as_str(), "GTC" | "GTD") && !self.is_paper()
+    }
+
+    /// The lock held while the holdings file and target ledgers are updated. Anything else
+    /// writing them (the redeem loop) must hold it too.
+    pub fn ledger_lock(&self) -> std::sync::Arc<std::sync::Mutex<()>> {
+        self.ledger_lock.clone()
+    }
+
+    /// GTC/GTD orders placed and not yet filled, cancelled or expired.
+    pub fn resting_orders(&self) -> Vec<RestingOrder> {
+        load_resting_orders(&resting_orders_path(&self.holdings_path))
+    }
+
+    /// The simulated account, if in paper mode.
+    pub fn paper_account(&self) -> Option<PaperAccount> {
+        self.paper
+            .as_ref()
+            .map(|p| PaperAccount::load(&p.account_path, p.starting_balance))
+    }
+
+    /// Copies `trade` using the sizing rules of the target it came from. Holdings bought for a
+    /// target are recorded against it, and a target's SELL only sells what was copied from it:
+    /// the same fraction the target sold of its position, or all of it (see [`sell_amount`]).
+    /// With GTC/GTD orders the copy rests at the target's price, and a target's SELL first
+    /// cancels our resting BUYs of that token.
+    pub async fn copy_trade(&self, trade: &TradePayload, target: &TargetConfig) -> Result<CopyTradeResult> {
+        let sold_fraction = self.record_target_trade(trade, target).await;
+        self.copy_recorded_trade(trade, target, sold_fraction).await
+    }
+
+    /// Adds a target's trade to its running position and returns the fraction of the position
+    /// a SELL closed (0 for a BUY). Call it for every trade of a target, before a filter can
+    /// skip the copy, so later SELLs are measured against the target's whole position.
+    pub async fn record_target_trade(&self, trade: &TradePayload, target: &TargetConfig) -> f64 {
+        if trade.side.eq_ignore_ascii_case("SELL") {
+            self.target_positions.record_sell(&target.wallet, trade).await
+        } else {
+            self.target_positions.record_buy(&target.wallet, trade).await;
+            0.0
+        }
+    }
+
+    /// [`Self::copy_trade`] for a trade already passed to [`Self::record_target_trade`];
+    /// `sold_fraction` is what that returned.
+    pub async fn copy_recorded_trade(
         &self,
         trade: &TradePayload,
-        size_multiplier: f64,
-        max_amount: Option<f64>,
+        target: &TargetConfig,
+        sold_fraction: f64,
     ) -> Result<CopyTradeResult> {
         let condition_id = trade.condition_id().unwrap_or("");
         let token_id = &trade.asset;
         let side_upper = trade.side.to_uppercase();
+        let ledger_path = target_ledger_path(&self.holdings_path);
 
         if side_upper == "SELL" {
-            let holdings_amount = get_holdings(&self.holdings_path, condition_id, token_id);
+            let fraction = sold_fraction;
+            if self.rests_orders() {
+                let buys = self
+                    .resting_orders()
+                    .into_iter()
+                    .filter(|o| o.side == OrderSide::Buy && o.token_id == *token_id && target.is_wallet(&o.target))
+                    .collect();
+                self.cancel_tracked_orders(buys, &format!("{} is selling", target.name())).await?;
+            }
+            if let Err(e) = self.update_ledgers(|| {
+                adopt_unledgered_holdings(&self.holdings_path, &ledger_path, &target.wallet, condition_id, token_id)
+            }) {
+                warn!("Could not attribute unledgered holdings to {}: {}", target.name(), e);
+            }
+            // Shares already offered by open sells cannot be sold again.
+            let mut held = get_target_holdings(&ledger_path, &target.wallet, condition_id, token_id);
+            let resting_sells: Vec<RestingOrder> = self
+                .resting_orders()
+                .into_iter()
+                .filter(|o| o.side == OrderSide::Sell && o.token_id == *token_id)
+                .collect();
+            held -= resting_sells.iter().filter(|o| target.is_wallet(&o.target)).map(RestingOrder::remaining).sum::<f64>();
+            let mut ours = get_holdings(&self.holdings_path, condition_id, token_id);
+            if !self.is_paper() {
+                ours -= match get_reserved(&self.clob, Some(token_id)).await {
+                    Ok(reserved) => reserved.shares_of(token_id),
+                    Err(e) => {
+                        warn!("Could not fetch open orders; counting only tracked sells: {}", e);
+                        resting_sells.iter().map(RestingOrder::remaining).sum()
+                    }
+                };
+            }
+            held = held.min(ours);
+            if held <= 0.0 {
+                warn!(
+                    "No holdings from {} for SELL: {} {}",
+                    target.name(),
+                    condition_id,
+                    &token_id[..token_id.len().min(20)]
+                );
+                return Ok(CopyTradeResult::failure("No holdings available to sell"));
+            }
+            let holdings_amount = sell_amount(held, fraction, target);
+            info!(
+                "{} sold {:.1}% of its position; selling {:.6} of {:.6} copied shares",
+                target.name(),
+                fraction * 100.0,
+                holdings_amount,
+                held
+            );
             if holdings_amount <= 0.0 {
-                warn!("No holdings for SELL: {} {}", condition_id, &token_id[..token_id.len().min(20)]);
-                return Ok(CopyTradeResult {
-                    success: false,
-                    order_id: None,
-                    transaction_hashes: None,
-                    error: Some("No holdings available to sell".to_string()),
-                });
+                if fraction > 0.0 {
+                    info!(
+                        "Skipping SELL for {}: {:.6} shares is below the {} share minimum order size",
+                        target.name(),
+                        held * fraction.min(1.0),
+                        MIN_ORDER_SHARES
+                    );
+                    return Ok(CopyTradeResult::failure("Sell below the minimum order size"));
+                }
+                return Ok(CopyTradeResult::failure("Nothing to sell"));
+            }
+            let params = self.market_params(token_id).await;
+            if self.rests_orders() {
+                let price = limit_price(OrderSide::Sell, trade.price, &params);
+                return self
+                    .place_limit_order(target, condition_id, token_id, OrderSide::Sell, holdings_amount, price, &params)
+                    .await;
+            }
+            let posted_at = Utc::now().timestamp();
+            let mut result = self
+                .place_market_order(token_id, OrderSide::Sell, holdings_amount, trade.price, &params)
+                .await?;
+            if !result.success {
+                return Ok(result);
             }
-            return self.place_market_sell(condition_id, token_id, holdings_amount).await;
+            let Some(fill) = self
+                .confirm_fill(&mut result, target, condition_id, OrderSide::Sell, token_id, holdings_amount, posted_at, trade.price)
+                .await
+            else {
+                return Ok(result);
+            };
+            if let Some(risk) = &self.risk {
+                risk.roll_day()?;
+            }
+            let pnl = self.update_ledgers(|| {
+                remove_holdings(&self.holdings_path, condition_id, token_id, fill.shares)?;
+                record_target_sell(&ledger_path, &target.wallet, condition_id, token_id, fill.shares, fill.usdc)
+            })?;
+            info!("Realized PnL for {}: {:.6} USDC", target.name(), pnl);
+            return Ok(result);
         }
 
-        let amount = (trade.price * trade.size * size_multiplier).max(1.0);
-        let amount = if let Some(max) = max_amount {
-            if amount > max {
-                (max * 0.5).min(max)
-            } else {
-                amount
+        let strategy = sizing_strategy(&target.sizing());
+        let input = self.sizing_input(strategy.as_ref(), trade, target).await;
+        let sized = match strategy.amount(&input) {
+            Ok(amount) => amount,
+            Err(e) => {
+                warn!("Sizing failed for {} ({}): {}", target.name(), strategy.name(), e);
+                return Ok(CopyTradeResult::failure(format!("Sizing failed: {}", e)));
             }
-        } else {
-            amount
         };
+        let amount = match target.max_order_amount {
+            Some(max) => sized.max(1.0).min(max),
+            None => sized.max(1.0),
+        };
+        info!(
+            "Sizing for {}: strategy={} target_amount={:.6} ({} @ {}) balance={:?} our_equity={:?} target_equity={:?} sized={:.6} cap={:?} amount={:.6}",
+            target.name(),
+            strategy.name(),
+            input.target_amount,
+            trade.size,
+            trade.price,
+            input.available_balance,
+            input.our_equity,
+            input.target_equity,
+            sized,
+            target.max_order_amount,
+            amount
+        );
 
-        let _ = self.clob.update_balance_allowance("COLLATERAL").await;
-        let _ = display_wallet_balance(&self.clob).await;
-        let check = validate_buy_order_balance(&self.clob, amount).await?;
+        let check = self.check_buy_balance(amount).await?;
         let amount = if !check.valid {
             if check.available <= 0.0 {
-                return Ok(CopyTradeResult {
-                    success: false,
-                    order_id: None,
-                    transaction_hashes: None,
-                    error: Some(format!("Insufficient USDC. Available: {}", check.available)),
-                });
+                return Ok(CopyTradeResult::failure(format!("Insufficient USDC. Available: {}", check.available)));
             }
             check.available
         } else {
             amount
         };
 
-        let result = self.place_market_buy(token_id, amount, trade.price).await?;
-        if result.success {
-            if let Some(taking) = result.transaction_hashes.as_ref() {
-                if !taking.is_empty() {}
+        if let Some(risk) = &self.risk {
+            if let Some(violation) = risk.check_buy(condition_id, token_id, amount)? {
+                warn!("Rejected BUY from {}: {}", target.name(), violation);
+                if matches!(violation, RiskViolation::DailyLossLimit { .. }) {
+                    self.cancel_resting_buys("daily loss limit reached").await?;
+                }
+                return Ok(CopyTradeResult::rejected(violation));
             }
-            let tokens_est = amount / trade.price;
-            add_holdings(&self.holdings_path, condition_id, token_id, tokens_est)?;
-            let _ = approve_tokens_after_buy(&self.provider, &self.wallet, self.chain_id, self.neg_risk).await;
+        }
+
+        let params = self.market_params(token_id).await;
+        if self.rests_orders() {
+            let price = limit_price(OrderSide::Buy, trade.price, &params);
+            return self
+                .place_limit_order(target, condition_id, token_id, OrderSide::Buy, amount / price, price, &params)
+                .await;
+        }
+
+        let posted_at = Utc::now().timestamp();
+        let mut result = self
+            .place_market_order(token_id, OrderSide::Buy, amount, trade.price, &params)
+            .await?;
+        if !result.success {
+            return Ok(result);
+        }
+        let Some(fill) = self
+            .confirm_fill(&mut result, target, condition_id, OrderSide::Buy, token_id, amount, posted_at, trade.price)
+            .await
+        else {
+            return Ok(result);
+        };
+        if let Some(risk) = &self.risk {
+            risk.record_buy(fill.usdc)?;
+        }
+        self.update_ledgers(|| {
+            add_holdings(&self.holdings_path, condition_id, token_id, fill.shares)?;
+            record_target_buy(&ledger_path, &target.wallet, condition_id, token_id, fill.shares, fill.usdc)
+        })?;
+        if !self.is_paper() {
+            let _ = approve_tokens_after_buy(&self.provider, &self.wallet, self.chain_id, params.neg_risk).await;
         }
         Ok(result)
     }
 
-    async fn place_market_buy(&self, token_id: &str, amount: f64, price: f64) -> Result<CopyTradeResult> {
-        let order_payload = self.build_market_order_payload(token_id, "BUY", amount, price);
-        self.post_market_order(order_payload).await
+    /// Looks up what `strategy` needs; values that cannot be fetched are left out.
+    async fn sizing_input(&self, strategy: &dyn SizingStrategy, trade: &TradePayload, target: &TargetConfig) -> SizingInput {
+        let mut input = SizingInput {
+            target_amount: trade.price * trade.size,
+            ..Default::default()
+        };
+        if !strategy.needs_balance() && !strategy.needs_equity() {
+            return input;
+        }
+        let account = self.paper_account();
+        let balance = match &account {
+            Some(account) => Some(account.usdc_balance.max(0.0)),
+            None => get_available_balance(&self.clob, None)
+                .await
+                .map_err(|e| warn!("Could not fetch available balance: {}", e))
+                .ok(),
+        };
+        input.available_balance = balance;
+        if strategy.needs_equity() {
+            input.our_equity = match &account {
+                Some(account) => Some(account.usdc_balance + account.positions.values().map(|p| p.cost).sum::<f64>()),
+                None => match (balance, self.portfolio_value(&format!("{:?}", self.wallet.address())).await) {
+                    (Some(balance), Some(value)) => Some(balance + value),
+                    _ => None,
+                },
+            };
+            input.target_equity = self.target_equity(&target.wallet).await;
+        }
+        input
+    }
+
+    /// The target's USDC on chain plus the value of its positions, to compare with our own
+    /// balance plus positions.
+    async fn target_equity(&self, wallet: &str) -> Option<f64> {
+        let value = self.portfolio_value(wallet).await?;
+        let owner = address_from_hex(wallet)
+            .map_err(|e| warn!("Invalid target wallet {}: {}", wallet, e))
+            .ok()?;
+        let cash = usdc_balance(&self.provider, self.chain_id, owner)
+            .await
+            .map_err(|e| warn!("Could not fetch USDC balance of {}: {}", wallet, e))
+            .ok()?;
+        Some(value + cash)
+    }
+
+    async fn portfolio_value(&self, user: &str) -> Option<f64> {
+        let url = self.data_api_url.as_deref()?;
+        get_portfolio_value(url, user)
+            .await
+            .map_err(|e| warn!("Could not fetch portfolio value of {}: {}", user, e))
+            .ok()
     }
 
-    async fn place_market_sell(&self, condition_id: &str, token_id: &str, amount: f64) -> Result<CopyTradeResult> {
-        let order_payload = self.build_market_order_payload(token_id, "SELL", amount, 0.5);
-        let result = self.post_market_order(order_payload).await?;
-        if result.success {
-            remove_holdings(&self.holdings_path, condition_id, token_id, amount)?;
+    async fn check_buy_balance(&self, amount: f64) -> Result<BalanceCheck> {
+        if let Some(account) = self.paper_account() {
+            let available = account.usdc_balance.max(0.0);
+            if available < amount {
+                warn!("Insufficient paper balance: required={:.6} available={:.6}", amount, available);
+            }
+            return Ok(BalanceCheck {
+                valid: available >= amount,
+                available,
+                required: amount,
+                balance: account.usdc_balance,
+                allowance: None,
+            });
         }
-        Ok(result)
+        let _ = self.clob.update_balance_allowance("COLLATERAL").await;
+        let _ = display_wallet_balance(&self.clob).await;
+        validate_buy_order_balance(&self.clob, amount).await
     }
 
-    fn build_market_order_payload(&self, token_id: &str, side: &str, amount: f64, price: f64) -> serde_json::Value {
-        serde_json::json!({
-            "tokenID": token_id,
-            "side": side,
-            "amount": amount,
-            "price": price,
-            "orderType": self.order_type,
-            "tickSize": self.tick_size,
-            "negRisk": self.neg_risk
+    /// Tick size and neg-risk flag of `token_id` from the CLOB, each falling back to the
+    /// configured value if its lookup fails. Trade-price simulation (backtests) always uses
+    /// the configured values.
+    pub async fn market_params(&self, token_id: &str) -> MarketParams {
+        let configured = MarketParams {
+            tick_size: self.tick_size.clone(),
+            neg_risk: self.neg_risk,
+        };
+        if self.paper.as_ref().is_some_and(|p| p.fill_model == PaperFillModel::TradePrice) {
+            return configured;
+        }
+        let short = &token_id[..token_id.len().min(20)];
+        let (tick_size, neg_risk) = tokio::join!(self.clob.get_tick_size(token_id), self.clob.get_neg_risk(token_id));
+        MarketParams {
+            tick_size: tick_size.unwrap_or_else(|e| {
+                warn!("Tick size lookup for {} failed, using {}: {}", short, configured.tick_size, e);
+                configured.tick_size.clone()
+            }),
+            neg_risk: neg_risk.unwrap_or_else(|e| {
+                warn!("Neg-risk lookup for {} failed, using {}: {}", short, configured.neg_risk, e);
+                configured.neg_risk
+            }),
+        }
+    }
+
+    /// Market order for `amount` (USDC for BUYs, shares for SELLs) at the target's price plus
+    /// the slippage allowance. Holdings are left to the caller, once the fill is confirmed.
+    async fn place_market_order(
+        &self,
+        token_id: &str,
+        side: OrderSide,
+        amount: f64,
+        trade_price: f64,
+        params: &MarketParams,
+    ) -> Result<CopyTradeResult> {
+        let worst = worst_price(side, trade_price, &self.slippage, params.tick());
+        self.post_market_order(token_id, side, amount, worst, trade_price, params).await
+    }
+
+    /// What the posted market order in `result` filled: `makingAmount`/`takingAmount` of the
+    /// response, else the matched size polled from the CLOB, priced from our trades. Logs an
+    /// unfilled remainder of `requested` (USDC for BUYs, shares for SELLs); an order the CLOB
+    /// reports as matching nothing turns `result` into a failure and returns `None`. When the
+    /// matched size cannot be read the order is tracked like a resting order, so that
+    /// [`Self::sync_resting_orders`] books its fill later, and `None` is returned.
+    #[allow(clippy::too_many_arguments)]
+    async fn confirm_fill(
+        &self,
+        result: &mut CopyTradeResult,
+        target: &TargetConfig,
+        condition_id: &str,
+        side: OrderSide,
+        token_id: &str,
+        requested: f64,
+        posted_at: i64,
+        reference_price: f64,
+    ) -> Option<Fill> {
+        let order_id = result.order_id.clone().unwrap_or_else(|| "N/A".to_string());
+        let mut fill = fill_from_response(side, result.making_amount, result.taking_amount);
+        if fill.is_none() && self.is_paper() {
+            fill = Some(Fill::default());
+        }
+        if fill.is_none() && result.order_id.is_some() {
+            fill = self.poll_fill(&order_id, token_id, posted_at, reference_price).await;
+        }
+        let Some(fill) = fill else {
+            let msg = match self.track_unconfirmed(result, target, condition_id, side, token_id, requested, reference_price) {
+                Ok(true) => format!("{} order {} fill unknown; tracked until the CLOB reports it", side.as_str(), order_id),
+                Ok(false) => format!("{} order {} fill unknown and not tracked; holdings unchanged", side.as_str(), order_id),
+                Err(e) => format!("{} order {} fill unknown and could not be tracked: {}", side.as_str(), order_id, e),
+            };
+            warn!("{}", msg);
+            result.error = Some(msg);
+            return None;
+        };
+        if fill.shares <= 0.0 {
+            let msg = format!("{} order {} filled nothing; holdings unchanged", side.as_str(), order_id);
+            warn!("{}", msg);
+            result.success = false;
+            result.error = Some(msg);
+            return None;
+        }
+        let (filled, unit) = match side {
+            OrderSide::Buy => (fill.usdc, "USDC"),
+            OrderSide::Sell => (fill.shares, "shares"),
+        };
+        // Order amounts are rounded to cents or hundredths of a share before posting.
+        if requested - filled > 0.01 {
+            info!(
+                "{} order {} filled {:.6} of {:.6} {}; the unfilled {:.6} was not executed ({})",
+                side.as_str(),
+                order_id,
+                filled,
+                requested,
+                unit,
+                requested - filled,
+                self.order_type
+            );
+        }
+        info!(
+            "{} order {} filled {:.6} shares for {:.6} USDC (avg {:.4})",
+            side.as_str(),
+            order_id,
+            fill.shares,
+            fill.usdc,
+            fill.avg_price().unwrap_or(0.0)
+        );
+        (result.making_amount, result.taking_amount) = match side {
+            OrderSide::Buy => (Some(fill.usdc), Some(fill.shares)),
+            OrderSide::Sell => (Some(fill.shares), Some(fill.usdc)),
+        };
+        Some(fill)
+    }
+
+    /// Records a posted order whose fill is unknown in the resting orders, sized at
+    /// `reference_price` (its fill is priced from our trades when booked). `false` if it has
+    /// no order id to look it up by.
+    #[allow(clippy::too_many_arguments)]
+    fn track_unconfirmed(
+        &self,
+        result: &CopyTradeResult,
+        target: &TargetConfig,
+        condition_id: &str,
+        side: OrderSide,
+        token_id: &str,
+        requested: f64,
+        reference_price: f64,
+    ) -> Result<bool> {
+        let Some(order_id) = result.order_id.clone().filter(|id| !id.is_empty()) else {
+            return Ok(false);
+        };
+        let size = match side {
+            OrderSide::Buy if reference_price > 0.0 => requested / reference_price,
+            OrderSide::Buy => return Ok(false),
+            OrderSide::Sell => requested,
+        };
+        self.update_resting_orders(|orders| {
+            orders.push(RestingOrder {
+                order_id,
+                target: target.wallet.clone(),
+                condition_id: condition_id.to_string(),
+                token_id: token_id.to_string(),
+                side,
+                price: reference_price,
+                size,
+                size_matched: 0.0,
+                placed_at: Utc::now().timestamp(),
+            })
+        })?;
+        Ok(true)
+    }
+
+    /// Polls `/data/order/{id}` until the order is done (or attempts run out) and prices its
+    /// matched size from our `/data/trades`, or at `reference_price` if none are found. `None`
+    /// if the order's matched size could not be read.
+    async fn poll_fill(&self, order_id: &str, token_id: &str, posted_at: i64, reference_price: f64) -> Option<Fill> {
+        let mut matched = None;
+        for attempt in 0..FILL_POLL_ATTEMPTS {
+            if attempt > 0 {
+                tokio::time::sleep(FILL_POLL_INTERVAL).await;
+            }
+            match self.clob.get_order(order_id).await {
+                Ok(Some(order)) => {
+                    matched = order.size_matched.as_deref().and_then(|m| m.parse::<f64>().ok());
+                    if !order.is_live() {
+                        break;
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Could not fetch order {}: {}", order_id, e),
+            }
+        }
+        let shares = matched?;
+        if shares <= 0.0 {
+            return Some(Fill::default());
+        }
+        let price = self.traded_price(order_id, token_id, posted_at).await.unwrap_or(reference_price);
+        Some(Fill { shares, usdc: shares * price })
+    }
+
+    /// Average price `order_id` filled at in our `/data/trades` since shortly before it was
+    /// posted; `None` if no matches are found.
+    async fn traded_price(&self, order_id: &str, token_id: &str, posted_at: i64) -> Option<f64> {
+        let after = posted_at.saturating_sub(60).max(0) as u64;
+        match self.clob.get_trades(token_id, after).await {
+            Ok(trades) => fill_from_trades(order_id, &trades).avg_price(),
+            Err(e) => {
+                warn!("Could not fetch trades of order {}: {}", order_id, e);
+                None
+            }
+        }
+    }
+
+    /// Address our orders are made by: the proxy wallet holding the funds, or our own.
+    fn maker(&self) -> Result<Address> {
+        match &self.clob.funder_address {
+            Some(funder) => address_from_hex(funder),
+            None => Ok(self.wallet.address()),
+        }
+    }
+
+    fn build_signed_market_order(
+        &self,
+        token_id: &str,
+        side: OrderSide,
+        amount: f64,
+        price: f64,
+        params: &MarketParams,
+    ) -> Result<SignedOrder> {
+        let order = Order::market(
+            generate_salt(),
+            self.maker()?,
+            self.wallet.address(),
+            token_id,
+            side,
+            amount,
+            price,
+            &params.tick_size,
+            0,
+            self.clob.signature_type,
+        )?;
+        sign_order(&self.wallet, order, self.chain_id, params.neg_risk)
+    }
+
+    /// Posts a market order with worst price `price` after checking the book can fill it
+    /// there; `reference_price` is the target's fill price, used by trade-price simulation.
+    async fn post_market_order(
+        &self,
+        token_id: &str,
+        side: OrderSide,
+        amount: f64,
+        price: f64,
+        reference_price: f64,
+        params: &MarketParams,
+    ) -> Result<CopyTradeResult> {
+        if let Some(paper) = &self.paper {
+            return self.simulate_market_order(paper, token_id, side, amount, price, reference_price).await;
+        }
+        match get_order_book(&self.clob.base_url, token_id).await {
+            Ok(book) => {
+                if let Err(reason) = check_book(&book, side, amount, price, &self.order_type) {
+                    let msg = format!("Skipped {} (target price {}): {}", side.as_str(), reference_price, reason);
+                    warn!("{}", msg);
+                    return Ok(CopyTradeResult::failure(msg));
+                }
+            }
+            Err(e) => warn!("No order book for slippage check, posting with worst price {}: {}", price, e),
+        }
+        let signed = match self.build_signed_market_order(token_id, side, amount, price, params) {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("Failed to build {} order: {}", side.as_str(), e);
+                return Ok(CopyTradeResult::failure(e.to_string()));
+            }
+        };
+        info!(
+            "Placing {} market order: {} at worst {} (target {}, type: {})",
+            side.as_str(),
+            amount,
+            price,
+            reference_price,
+            self.order_type
+        );
+        let resp = match self.post_signed_order(&signed).await {
+            Ok(r) => r,
+            Err(msg) => return Ok(CopyTradeResult::failure(msg)),
+        };
+        info!(
+            "{} order executed! OrderID: {}, Status: {}",
+            side.as_str(),
+            resp.order_id.as_deref().unwrap_or("N/A"),
+            resp.status.as_deref().unwrap_or("N/A")
+        );
+        Ok(CopyTradeResult {
+            success: true,
+            order_id: resp.order_id,
+            transaction_hashes: resp.transactions_hashes,
+            status: resp.status,
+            making_amount: resp.making_amount.and_then(|a| a.parse().ok()),
+            taking_amount: resp.taking_amount.and_then(|a| a.parse().ok()),
+            ..Default::default()
+        })
+    }
+
+    /// Posts `signed`; `Err` is the (logged) reason the CLOB did not accept it.
+    async fn post_signed_order(&self, signed: &SignedOrder) -> std::result::Result<PostOrderResponse, String> {
+        let body = signed.to_post_body(&self.clob.creds.api_key, &self.order_type);
+        let resp = match self.clob.post_order(&body, &signed.order_id()).await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("Order rejected: {}", e);
+                return Err(e.to_string());
+            }
+        };
+        let error_msg = resp.error_msg.clone().filter(|m| !m.is_empty());
+        if !resp.success || error_msg.is_some() {
+            let msg = error_msg.unwrap_or_else(|| "Order not accepted by CLOB".to_string());
+            warn!("Order failed: {}", msg);
+            return Err(msg);
+        }
+        Ok(resp)
+    }
+
+    /// Posts a GTC/GTD order for `size` shares at `price` and tracks it as resting. Holdings
+    /// and the target ledger change only as [`Self::sync_resting_orders`] confirms fills.
+    #[allow(clippy::too_many_arguments)]
+    async fn place_limit_order(
+        &self,
+        target: &TargetConfig,
+        condition_id: &str,
+        token_id: &str,
+        side: OrderSide,
+        size: f64,
+        price: f64,
+        params: &MarketParams,
+    ) -> Result<CopyTradeResult> {
+        let now = Utc::now().timestamp();
+        let expiration = if self.order_type == "GTD" {
+            now as u64 + GTD_SECURITY_THRESHOLD_SECS + self.order_timeout_secs
+        } else {
+            0
+        };
+        let signed = self
+            .maker()
+            .and_then(|maker| {
+                Order::limit(
+                    generate_salt(),
+                    maker,
+                    self.wallet.address(),
+                    token_id,
+                    side,
+                    size,
+                    price,
+                    &params.tick_size,
+                    expiration,
+                    0,
+                    self.clob.signature_type,
+                )
+            })
+        .and_then(|order| sign_order(&self.wallet, order, self.chain_id, params.neg_risk));
+        let signed = match signed {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("Failed to build {} order: {}", side.as_str(), e);
+                return Ok(CopyTradeResult::failure(e.to_string()));
+            }
+        };
+        info!(
+            "Placing {} {} order: {:.4} shares at {} (timeout {}s)",
+            self.order_type,
+            side.as_str(),
+            size,
+            price,
+            self.order_timeout_secs
+        );
+        let resp = match self.post_signed_order(&signed).await {
+            Ok(r) => r,
+            Err(msg) => return Ok(CopyTradeResult::failure(msg)),
+        };
+        let Some(order_id) = resp.order_id.clone().filter(|id| !id.is_empty()) else {
+            warn!("{} order accepted without an order id; not tracked", side.as_str());
+            return Ok(CopyTradeResult::failure("Order accepted without an order id"));
+        };
+        self.update_resting_orders(|orders| {
+            orders.push(RestingOrder {
+                order_id: order_id.clone(),
+                target: target.wallet.clone(),
+                condition_id: condition_id.to_string(),
+                token_id: token_id.to_string(),
+                side,
+                price,
+                size,
+                size_matched: 0.0,
+                placed_at: now,
+            })
+        })?;
+        info!(
+            "{} order {} resting for {} (status {})",
+            side.as_str(),
+            order_id,
+            target.name(),
+            resp.status.as_deref().unwrap_or("N/A")
+        );
+        Ok(CopyTradeResult {
+            success: true,
+            order_id: Some(order_id),
+            transaction_hashes: resp.transactions_hashes,
+            status: resp.status,
+            ..Default::default()
         })
     }
 
-    async fn post_market_order(&self, _order: serde_json::Value) -> Result<CopyTradeResult> {
-        info!("Placeholder: market order would be sent to CLOB (full EIP-712 order signing not yet implemented in Rust)");
+    /// Checks every resting order with the CLOB: applies fills confirmed since the last check,
+    /// cancels orders still open after the order timeout, and stops tracking orders that can
+    /// no longer fill.
+    pub async fn sync_resting_orders(&self) -> Result<()> {
+        let now = Utc::now().timestamp();
+        for order in self.resting_orders() {
+            if order.is_timed_out(self.order_timeout_secs, now) {
+                info!(
+                    "Cancelling {} order {} after {}s: {:.4} of {:.4} shares filled",
+                    order.side.as_str(),
+                    order.order_id,
+                    self.order_timeout_secs,
+                    order.size_matched,
+                    order.size
+                );
+                if let Err(e) = self.clob.cancel_order(&order.order_id).await {
+                    warn!("Could not cancel order {}: {}", order.order_id, e);
+                }
+            }
+            self.refresh_resting_order(&order).await?;
+        }
+        Ok(())
+    }
+
+    /// Cancels every tracked resting order, e.g. on shutdown. Returns how many were cancelled.
+    pub async fn cancel_all_resting(&self, reason: &str) -> Result<usize> {
+        self.cancel_tracked_orders(self.resting_orders(), reason).await
+    }
+
+    /// Cancels tracked resting BUYs, leaving sells that only reduce exposure.
+    pub async fn cancel_resting_buys(&self, reason: &str) -> Result<usize> {
+        let buys = self.resting_orders().into_iter().filter(|o| o.side == OrderSide::Buy).collect();
+        self.cancel_tracked_orders(buys, reason).await
+    }
+
+    /// Cancels `orders` in one batch, then applies their last fills and stops tracking them.
+    async fn cancel_tracked_orders(&self, orders: Vec<RestingOrder>, reason: &str) -> Result<usize> {
+        if orders.is_empty() {
+            return Ok(0);
+        }
+        let ids: Vec<String> = orders.iter().map(|o| o.order_id.clone()).collect();
+        info!("{}; cancelling {} resting order(s): {}", reason, ids.len(), ids.join(", "));
+        let cancelled = match self.clob.cancel_orders(&ids).await {
+            Ok(out) => {
+                for (id, why) in &out.not_canceled {
+                    warn!("Order {} not cancelled: {}", id, why);
+                }
+                out.canceled.len()
+            }
+            Err(e) => {
+                warn!("Could not cancel resting orders: {}", e);
+                0
+            }
+        };
+        for order in &orders {
+            self.refresh_resting_order(order).await?;
+        }
+        Ok(cancelled)
+    }
+
+    /// Applies fills of `order` the CLOB reports beyond those already applied, and drops the
+    /// order once it is no longer live (or unknown to the CLOB).
+    async fn refresh_resting_order(&self, order: &RestingOrder) -> Result<()> {
+        let status = match self.clob.get_order(&order.order_id).await {
+            Ok(status) => status,
+            Err(e) => {
+                warn!("Could not fetch order {}: {}", order.order_id, e);
+                return Ok(());
+            }
+        };
+        let (size_matched, live) = match &status {
+            Some(s) => (
+                s.size_matched.as_deref().and_then(|m| m.parse().ok()).unwrap_or(order.size_matched),
+                s.is_live(),
+            ),
+            None => (order.size_matched, false),
+        };
+        let mut tracked = None;
+        self.update_resting_orders(|orders| {
+            if let Some(i) = orders.iter().position(|o| o.order_id == order.order_id) {
+                let filled = orders[i].fill_delta(size_matched);
+                orders[i].size_matched += filled;
+                tracked = Some((filled, orders[i].size_matched));
+                if !live {
+                    orders.remove(i);
+                }
+            }
+        })?;
+        let Some((filled, total)) = tracked else {
+            return Ok(());
+        };
+        if filled > 0.0 {
+            self.apply_resting_fill(order, filled).await?;
+        }
+        if !live {
+            info!(
+                "{} order {} closed ({}): {:.4} of {:.4} shares filled",
+                order.side.as_str(),
+                order.order_id,
+                status.as_ref().and_then(|s| s.status.as_deref()).unwrap_or("unknown to the CLOB"),
+                total,
+                order.size
+            );
+        }
+        Ok(())
+    }
+
+    /// Books `shares` newly filled by `order`, priced from our trades like [`Self::poll_fill`],
+    /// or at the order's price if none are found.
+    async fn apply_resting_fill(&self, order: &RestingOrder, shares: f64) -> Result<()> {
+        let price = self
+            .traded_price(&order.order_id, &order.token_id, order.placed_at)
+            .await
+            .unwrap_or(order.price);
+        let usdc = shares * price;
+        let ledger_path = target_ledger_path(&self.holdings_path);
+        info!(
+            "{} order {} filled {:.4} shares at {:.4} ({:.6} USDC)",
+            order.side.as_str(),
+            order.order_id,
+            shares,
+            price,
+            usdc
+        );
+        match order.side {
+            OrderSide::Buy => {
+                if let Some(risk) = &self.risk {
+                    risk.record_buy(usdc)?;
+                }
+                self.update_ledgers(|| {
+                    add_holdings(&self.holdings_path, &order.condition_id, &order.token_id, shares)?;
+                    record_target_buy(&ledger_path, &order.target, &order.condition_id, &order.token_id, shares, usdc)
+                })?;
+                let neg_risk = self.market_params(&order.token_id).await.neg_risk;
+                let _ = approve_tokens_after_buy(&self.provider, &self.wallet, self.chain_id, neg_risk).await;
+            }
+            OrderSide::Sell => {
+                if let Some(risk) = &self.risk {
+                    risk.roll_day()?;
+                }
+                let pnl = self.update_ledgers(|| {
+                    remove_holdings(&self.holdings_path, &order.condition_id, &order.token_id, shares)?;
+                    record_target_sell(&ledger_path, &order.target, &order.condition_id, &order.token_id, shares, usdc)
+                })?;
+                info!("Realized PnL for {}: {:.6} USDC", order.target, pnl);
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs `update` of the holdings file and target ledgers under the ledger lock.
+    fn update_ledgers<T>(&self, update: impl FnOnce() -> Result<T>) -> Result<T> {
+        let _guard = self.ledger_lock.lock().unwrap();
+        update()
+    }
+
+    fn update_resting_orders(&self, update: impl FnOnce(&mut Vec<RestingOrder>)) -> Result<()> {
+        let _guard = self.resting_lock.lock().unwrap();
+        let path = resting_orders_path(&self.holdings_path);
+        let mut orders = load_resting_orders(&path);
+        update(&mut orders);
+        save_resting_orders(&path, &orders)
+    }
+
+    async fn simulate_market_order(
+        &self,
+        paper: &PaperSettings,
+        token_id: &str,
+        side: OrderSide,
+        amount: f64,
+        price: f64,
+        reference_price: f64,
+    ) -> Result<CopyTradeResult> {
+        let fill = match paper.fill_model {
+            PaperFillModel::OrderBook => match get_order_book(&self.clob.base_url, token_id).await {
+                Ok(book) => match check_book(&book, side, amount, price, &self.order_type) {
+                    Ok(fill) => fill,
+                    Err(reason) => {
+                        let msg = format!("Skipped paper {} (target price {}): {}", side.as_str(), reference_price, reason);
+                        warn!("{}", msg);
+                        return Ok(CopyTradeResult::failure(msg));
+                    }
+                },
+                Err(e) => {
+                    warn!("Paper order skipped, no order book: {}", e);
+                    return Ok(CopyTradeResult::failure(e.to_string()));
+                }
+            },
+            PaperFillModel::TradePrice if reference_price > 0.0 => match side {
+                OrderSide::Buy => SimulatedFill { shares: amount / reference_price, usdc: amount, complete: true },
+                OrderSide::Sell => SimulatedFill { shares: amount, usdc: amount * reference_price, complete: true },
+            },
+            PaperFillModel::TradePrice => SimulatedFill::default(),
+        };
+        if fill.shares <= 0.0 {
+            let msg = format!("No liquidity within price {} for paper {} ({})", price, side.as_str(), self.order_type);
+            warn!("{}", msg);
+            return Ok(CopyTradeResult::failure(msg));
+        }
+
+        let mut account = PaperAccount::load(&paper.account_path, paper.starting_balance);
+        let pnl = account.apply_fill(token_id, side, &fill);
+        account.save(&paper.account_path)?;
+        info!(
+            "[PAPER] {} filled {:.4} shares for {:.6} USDC (avg {:.4}{}); balance={:.6} realized_pnl={:.6}{}",
+            side.as_str(),
+            fill.shares,
+            fill.usdc,
+            fill.avg_price().unwrap_or(0.0),
+            if fill.complete { "" } else { ", partial" },
+            account.usdc_balance,
+            account.realized_pnl,
+            if side == OrderSide::Sell { format!(" (this sell {:.6})", pnl) } else { String::new() }
+        );
+        let (making, taking) = match side {
+            OrderSide::Buy => (fill.usdc, fill.shares),
+            OrderSide::Sell => (fill.shares, fill.usdc),
+        };
         Ok(CopyTradeResult {
-            success: false,
-            order_id: None,
+            success: true,
+            order_id: Some(format!("paper-{}", generate_salt())),
             transaction_hashes: None,
-            error: Some("Rust CLOB market order posting not yet implemented - use TS bot for execution".to_string()),
+            status: Some(if fill.complete { "matched" } else { "partial" }.to_string()),
+            making_amount: Some(making),
+            taking_amount: Some(taking),
+            ..Default::default()
         })
     }
 }
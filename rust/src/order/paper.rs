@@ -0,0 +1,170 @@
+use super::OrderSide;
+use crate::clob::OrderBook;
+use crate::holdings::TargetPosition;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::error;
+
+/// Result of walking the book for a simulated market order.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SimulatedFill {
+    pub shares: f64,
+    pub usdc: f64,
+    /// Whether the whole requested amount was available within the price limit.
+    pub complete: bool,
+}
+
+impl SimulatedFill {
+    pub fn avg_price(&self) -> Option<f64> {
+        (self.shares > 0.0).then(|| self.usdc / self.shares)
+    }
+}
+
+/// Walks the book like the matching engine would for a market order: a BUY spends `amount`
+/// USDC on asks priced at or below `limit_price`, a SELL sells `amount` shares into bids at or
+/// above it. FOK orders that cannot be filled completely fill nothing; FAK keeps the partial fill.
+pub fn simulate_market_fill(book: &OrderBook, side: OrderSide, amount: f64, limit_price: f64, order_type: &str) -> SimulatedFill {
+    let mut levels: Vec<(f64, f64)> = match side {
+        OrderSide::Buy => &book.asks,
+        OrderSide::Sell => &book.bids,
+    }
+    .iter()
+    .filter_map(|l| Some((l.price.parse::<f64>().ok()?, l.size.parse::<f64>().ok()?)))
+    .filter(|(p, s)| *p > 0.0 && *s > 0.0)
+    .collect();
+    match side {
+        OrderSide::Buy => levels.sort_by(|a, b| a.0.total_cmp(&b.0)),
+        OrderSide::Sell => levels.sort_by(|a, b| b.0.total_cmp(&a.0)),
+    }
+
+    const EPS: f64 = 1e-9;
+    let mut fill = SimulatedFill::default();
+    let mut remaining = amount;
+    for (price, size) in levels {
+        let crosses = match side {
+            OrderSide::Buy => price <= limit_price + EPS,
+            OrderSide::Sell => price + EPS >= limit_price,
+        };
+        if !crosses || remaining <= EPS {
+            break;
+        }
+        let shares = match side {
+            OrderSide::Buy => size.min(remaining / price),
+            OrderSide::Sell => size.min(remaining),
+        };
+        fill.shares += shares;
+        fill.usdc += shares * price;
+        remaining -= match side {
+            OrderSide::Buy => shares * price,
+            OrderSide::Sell => shares,
+        };
+    }
+    fill.complete = remaining <= EPS;
+    if !fill.complete && order_type.eq_ignore_ascii_case("FOK") {
+        return SimulatedFill::default();
+    }
+    fill
+}
+
+/// Simulated USDC balance and positions of a paper-trading run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaperAccount {
+    pub starting_balance: f64,
+    pub usdc_balance: f64,
+    /// USDC from sells minus the cost of the shares they closed.
+    pub realized_pnl: f64,
+    #[serde(default)]
+    pub fills: u64,
+    /// token id -> simulated position
+    #[serde(default)]
+    pub positions: HashMap<String, TargetPosition>,
+}
+
+/// `paper-account.json` in the same directory as the paper holdings file.
+pub fn paper_account_path(holdings_path: &Path) -> PathBuf {
+    holdings_path.with_file_name("paper-account.json")
+}
+
+impl PaperAccount {
+    pub fn new(starting_balance: f64) -> Self {
+        Self {
+            starting_balance,
+            usdc_balance: starting_balance,
+            realized_pnl: 0.0,
+            fills: 0,
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Loads the account, starting a fresh one with `starting_balance` if the file is missing.
+    pub fn load(path: &Path, starting_balance: f64) -> Self {
+        if !path.exists() {
+            return Self::new(starting_balance);
+        }
+        match std::fs::read_to_string(path).map(|s| serde_json::from_str(&s)) {
+            Ok(Ok(account)) => account,
+            Ok(Err(e)) => {
+                error!("Failed to parse paper account: {}", e);
+                Self::new(starting_balance)
+            }
+            Err(e) => {
+                error!("Failed to load paper account: {}", e);
+                Self::new(starting_balance)
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Books a simulated fill and returns the PnL it realized (zero for buys).
+    pub fn apply_fill(&mut self, token_id: &str, side: OrderSide, fill: &SimulatedFill) -> f64 {
+        if fill.shares <= 0.0 {
+            return 0.0;
+        }
+        self.fills += 1;
+        match side {
+            OrderSide::Buy => {
+                self.usdc_balance -= fill.usdc;
+                let position = self.positions.entry(token_id.to_string()).or_default();
+                position.shares += fill.shares;
+                position.cost += fill.usdc;
+                0.0
+            }
+            OrderSide::Sell => {
+                self.usdc_balance += fill.usdc;
+                let mut cost_closed = 0.0;
+                if let Some(position) = self.positions.get_mut(token_id) {
+                    let fraction = if position.shares > 0.0 { (fill.shares / position.shares).min(1.0) } else { 1.0 };
+                    cost_closed = position.cost * fraction;
+                    position.shares -= fill.shares;
+                    position.cost -= cost_closed;
+                    if position.shares <= 1e-9 {
+                        self.positions.remove(token_id);
+                    }
+                }
+                let pnl = fill.usdc - cost_closed;
+                self.realized_pnl += pnl;
+                pnl
+            }
+        }
+    }
+
+    /// Closes a position at resolution for `payout_per_share` (1 for the winning outcome, 0 for a
+    /// losing one) and returns the PnL it realized.
+    pub fn settle(&mut self, token_id: &str, payout_per_share: f64) -> Option<f64> {
+        let position = self.positions.remove(token_id)?;
+        let payout = position.shares * payout_per_share;
+        self.usdc_balance += payout;
+        let pnl = payout - position.cost;
+        self.realized_pnl += pnl;
+        Some(pnl)
+    }
+}
@@ -0,0 +1,69 @@
+use super::OrderSide;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::error;
+
+/// A GTC/GTD copy order resting on the book. Holdings only include the `size_matched` part.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RestingOrder {
+    pub order_id: String,
+    /// Wallet of the target the order copies.
+    pub target: String,
+    pub condition_id: String,
+    pub token_id: String,
+    pub side: OrderSide,
+    pub price: f64,
+    /// Shares ordered.
+    pub size: f64,
+    /// Shares filled and already applied to holdings.
+    #[serde(default)]
+    pub size_matched: f64,
+    /// Unix seconds.
+    pub placed_at: i64,
+}
+
+impl RestingOrder {
+    pub fn remaining(&self) -> f64 {
+        (self.size - self.size_matched).max(0.0)
+    }
+
+    /// Shares filled since the last update, given the CLOB's total `size_matched`.
+    pub fn fill_delta(&self, size_matched: f64) -> f64 {
+        (size_matched.min(self.size) - self.size_matched).max(0.0)
+    }
+
+    pub fn is_timed_out(&self, timeout_secs: u64, now: i64) -> bool {
+        now - self.placed_at >= timeout_secs as i64
+    }
+}
+
+/// `resting-orders.json` in the same directory as the holdings file.
+pub fn resting_orders_path(holdings_path: &Path) -> PathBuf {
+    holdings_path.with_file_name("resting-orders.json")
+}
+
+pub fn load_resting_orders(path: &Path) -> Vec<RestingOrder> {
+    if !path.exists() {
+        return Vec::new();
+    }
+    match std::fs::read_to_string(path).map(|s| serde_json::from_str(&s)) {
+        Ok(Ok(orders)) => orders,
+        Ok(Err(e)) => {
+            error!("Failed to parse resting orders: {}", e);
+            Vec::new()
+        }
+        Err(e) => {
+            error!("Failed to load resting orders: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub fn save_resting_orders(path: &Path, orders: &[RestingOrder]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(orders)?)?;
+    Ok(())
+}
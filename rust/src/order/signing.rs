@@ -0,0 +1,326 @@
+use crate::chain::{address_from_hex, get_contract_config};
+use anyhow::{Context, Result};
+use ethers::abi::Token;
+use ethers::signers::LocalWallet;
+use ethers::types::{Address, H256, U256};
+use ethers::utils::{keccak256, to_checksum};
+use serde::{Deserialize, Serialize};
+
+const DOMAIN_TYPE: &str = "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+const DOMAIN_NAME: &str = "Polymarket CTF Exchange";
+const DOMAIN_VERSION: &str = "1";
+const ORDER_TYPE: &str = "Order(uint256 salt,address maker,address signer,address taker,uint256 tokenId,uint256 makerAmount,uint256 takerAmount,uint256 expiration,uint256 nonce,uint256 feeRateBps,uint8 side,uint8 signatureType)";
+const COLLATERAL_DECIMALS: u32 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl OrderSide {
+    pub fn parse(side: &str) -> Result<Self> {
+        match side.to_uppercase().as_str() {
+            "BUY" => Ok(OrderSide::Buy),
+            "SELL" => Ok(OrderSide::Sell),
+            _ => anyhow::bail!("Invalid trade side: {}", side),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderSide::Buy => "BUY",
+            OrderSide::Sell => "SELL",
+        }
+    }
+
+    fn as_u8(&self) -> u8 {
+        match self {
+            OrderSide::Buy => 0,
+            OrderSide::Sell => 1,
+        }
+    }
+}
+
+/// Decimal places used when rounding price, size and amount for a given tick size
+/// (same table as the TS clob-client `ROUNDING_CONFIG`).
+#[derive(Debug, Clone, Copy)]
+pub struct RoundConfig {
+    pub price: u32,
+    pub size: u32,
+    pub amount: u32,
+}
+
+pub fn round_config(tick_size: &str) -> RoundConfig {
+    match tick_size {
+        "0.1" => RoundConfig { price: 1, size: 2, amount: 3 },
+        "0.001" => RoundConfig { price: 3, size: 2, amount: 5 },
+        "0.0001" => RoundConfig { price: 4, size: 2, amount: 6 },
+        _ => RoundConfig { price: 2, size: 2, amount: 4 },
+    }
+}
+
+/// CTF Exchange order, field for field as hashed by the exchange contract.
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub salt: u64,
+    pub maker: Address,
+    pub signer: Address,
+    pub taker: Address,
+    pub token_id: U256,
+    pub maker_amount: U256,
+    pub taker_amount: U256,
+    pub expiration: U256,
+    pub nonce: U256,
+    pub fee_rate_bps: U256,
+    pub side: OrderSide,
+    pub signature_type: u8,
+}
+
+#[derive(Debug, Clone)]
+pub struct SignedOrder {
+    pub order: Order,
+    pub signature: String,
+    /// EIP-712 order hash, which the CLOB uses as the order id.
+    pub hash: H256,
+}
+
+impl Order {
+    /// Builds a market order the way the TS clob-client `createMarketOrder` does:
+    /// BUY amounts are USDC to spend, SELL amounts are shares to sell.
+    #[allow(clippy::too_many_arguments)]
+    pub fn market(
+        salt: u64,
+        maker: Address,
+        signer: Address,
+        token_id: &str,
+        side: OrderSide,
+        amount: f64,
+        price: f64,
+        tick_size: &str,
+        fee_rate_bps: u64,
+        signature_type: u8,
+    ) -> Result<Self> {
+        let (maker_amount, taker_amount) = market_order_amounts(side, amount, price, tick_size)?;
+        Ok(Order {
+            salt,
+            maker,
+            signer,
+            taker: Address::zero(),
+            token_id: U256::from_dec_str(token_id).context("Invalid token id")?,
+            maker_amount,
+            taker_amount,
+            expiration: U256::zero(),
+            nonce: U256::zero(),
+            fee_rate_bps: U256::from(fee_rate_bps),
+            side,
+            signature_type,
+        })
+    }
+
+    /// Builds a limit order the way the TS clob-client `createOrder` does: `size` shares at
+    /// `price` on either side. `expiration` is a unix timestamp for GTD orders, 0 otherwise.
+    #[allow(clippy::too_many_arguments)]
+    pub fn limit(
+        salt: u64,
+        maker: Address,
+        signer: Address,
+        token_id: &str,
+        side: OrderSide,
+        size: f64,
+        price: f64,
+        tick_size: &str,
+        expiration: u64,
+        fee_rate_bps: u64,
+        signature_type: u8,
+    ) -> Result<Self> {
+        let (maker_amount, taker_amount) = limit_order_amounts(side, size, price, tick_size)?;
+        Ok(Order {
+            salt,
+            maker,
+            signer,
+            taker: Address::zero(),
+            token_id: U256::from_dec_str(token_id).context("Invalid token id")?,
+            maker_amount,
+            taker_amount,
+            expiration: U256::from(expiration),
+            nonce: U256::zero(),
+            fee_rate_bps: U256::from(fee_rate_bps),
+            side,
+            signature_type,
+        })
+    }
+
+    pub fn struct_hash(&self) -> [u8; 32] {
+        keccak256(ethers::abi::encode(&[
+            Token::FixedBytes(keccak256(ORDER_TYPE).to_vec()),
+            Token::Uint(U256::from(self.salt)),
+            Token::Address(self.maker),
+            Token::Address(self.signer),
+            Token::Address(self.taker),
+            Token::Uint(self.token_id),
+            Token::Uint(self.maker_amount),
+            Token::Uint(self.taker_amount),
+            Token::Uint(self.expiration),
+            Token::Uint(self.nonce),
+            Token::Uint(self.fee_rate_bps),
+            Token::Uint(U256::from(self.side.as_u8())),
+            Token::Uint(U256::from(self.signature_type)),
+        ]))
+    }
+}
+
+impl SignedOrder {
+    /// The order id the CLOB will assign: the `0x`-prefixed order hash.
+    pub fn order_id(&self) -> String {
+        format!("{:?}", self.hash)
+    }
+
+    /// JSON body for `POST /order`, matching the TS clob-client `orderToJson`.
+    pub fn to_post_body(&self, owner: &str, order_type: &str) -> serde_json::Value {
+        let o = &self.order;
+        serde_json::json!({
+            "order": {
+                "salt": o.salt,
+                "maker": to_checksum(&o.maker, None),
+                "signer": to_checksum(&o.signer, None),
+                "taker": to_checksum(&o.taker, None),
+                "tokenId": o.token_id.to_string(),
+                "makerAmount": o.maker_amount.to_string(),
+                "takerAmount": o.taker_amount.to_string(),
+                "expiration": o.expiration.to_string(),
+                "nonce": o.nonce.to_string(),
+                "feeRateBps": o.fee_rate_bps.to_string(),
+                "side": o.side.as_str(),
+                "signatureType": o.signature_type,
+                "signature": self.signature,
+            },
+            "owner": owner,
+            "orderType": order_type,
+        })
+    }
+}
+
+pub fn exchange_domain_separator(chain_id: u64, neg_risk: bool) -> Result<[u8; 32]> {
+    let cfg = get_contract_config(chain_id);
+    let exchange = address_from_hex(if neg_risk { &cfg.neg_risk_exchange } else { &cfg.exchange })?;
+    Ok(keccak256(ethers::abi::encode(&[
+        Token::FixedBytes(keccak256(DOMAIN_TYPE).to_vec()),
+        Token::FixedBytes(keccak256(DOMAIN_NAME).to_vec()),
+        Token::FixedBytes(keccak256(DOMAIN_VERSION).to_vec()),
+        Token::Uint(U256::from(chain_id)),
+        Token::Address(exchange),
+    ])))
+}
+
+pub fn order_hash(order: &Order, chain_id: u64, neg_risk: bool) -> Result<H256> {
+    let mut prefixed: Vec<u8> = vec![0x19, 0x01];
+    prefixed.extend_from_slice(&exchange_domain_separator(chain_id, neg_risk)?);
+    prefixed.extend_from_slice(&order.struct_hash());
+    Ok(H256::from(keccak256(prefixed)))
+}
+
+pub fn sign_order(wallet: &LocalWallet, order: Order, chain_id: u64, neg_risk: bool) -> Result<SignedOrder> {
+    let digest = order_hash(&order, chain_id, neg_risk)?;
+    let sig = wallet.sign_hash(digest)?;
+    Ok(SignedOrder {
+        order,
+        signature: format!("0x{}", hex::encode(sig.to_vec())),
+        hash: digest,
+    })
+}
+
+/// Same scheme as the TS order utils `generateSalt`: `round(random() * now_ms)`.
+pub fn generate_salt() -> u64 {
+    let now_ms = chrono::Utc::now().timestamp_millis() as f64;
+    (ethers::core::rand::random::<f64>() * now_ms).round() as u64
+}
+
+/// Maker/taker amounts in 6-decimal units, mirroring `getMarketOrderRawAmounts`.
+pub fn market_order_amounts(side: OrderSide, amount: f64, price: f64, tick_size: &str) -> Result<(U256, U256)> {
+    let rc = round_config(tick_size);
+    let raw_price = round_down(price, rc.price);
+    if raw_price <= 0.0 {
+        anyhow::bail!("Invalid order price: {}", price);
+    }
+    let raw_maker = round_down(amount, rc.size);
+    let raw_taker = match side {
+        OrderSide::Buy => raw_maker / raw_price,
+        OrderSide::Sell => raw_maker * raw_price,
+    };
+    let raw_taker = round_amount(raw_taker, rc.amount);
+    Ok((parse_units(raw_maker)?, parse_units(raw_taker)?))
+}
+
+/// Maker/taker amounts in 6-decimal units for `size` shares at `price`, mirroring
+/// `getOrderRawAmounts`: BUYs pay USDC for shares, SELLs give shares for USDC.
+pub fn limit_order_amounts(side: OrderSide, size: f64, price: f64, tick_size: &str) -> Result<(U256, U256)> {
+    let rc = round_config(tick_size);
+    let raw_price = round_normal(price, rc.price);
+    if raw_price <= 0.0 {
+        anyhow::bail!("Invalid order price: {}", price);
+    }
+    let shares = round_down(size, rc.size);
+    let usdc = round_amount(shares * raw_price, rc.amount);
+    match side {
+        OrderSide::Buy => Ok((parse_units(usdc)?, parse_units(shares)?)),
+        OrderSide::Sell => Ok((parse_units(shares)?, parse_units(usdc)?)),
+    }
+}
+
+/// Rounds a computed amount to `decimals` places the way the clob-client does: up at four
+/// extra places first (absorbing float error), then down if still too long.
+fn round_amount(num: f64, decimals: u32) -> f64 {
+    if decimal_places(num) <= decimals {
+        return num;
+    }
+    let up = round_up(num, decimals + 4);
+    if decimal_places(up) > decimals {
+        round_down(up, decimals)
+    } else {
+        up
+    }
+}
+
+fn decimal_places(num: f64) -> u32 {
+    if num.fract() == 0.0 {
+        return 0;
+    }
+    let s = num.to_string();
+    s.split('.').nth(1).map(|d| d.len() as u32).unwrap_or(0)
+}
+
+fn round_down(num: f64, decimals: u32) -> f64 {
+    if decimal_places(num) <= decimals {
+        return num;
+    }
+    let p = 10f64.powi(decimals as i32);
+    (num * p).floor() / p
+}
+
+fn round_normal(num: f64, decimals: u32) -> f64 {
+    if decimal_places(num) <= decimals {
+        return num;
+    }
+    let p = 10f64.powi(decimals as i32);
+    (num * p).round() / p
+}
+
+fn round_up(num: f64, decimals: u32) -> f64 {
+    if decimal_places(num) <= decimals {
+        return num;
+    }
+    let p = 10f64.powi(decimals as i32);
+    (num * p).ceil() / p
+}
+
+fn parse_units(num: f64) -> Result<U256> {
+    let s = num.to_string();
+    let (int_part, frac_part) = s.split_once('.').unwrap_or((&s, ""));
+    if frac_part.len() > COLLATERAL_DECIMALS as usize {
+        anyhow::bail!("Too many decimals for amount: {}", s);
+    }
+    let padded = format!("{}{:0<width$}", int_part, frac_part, width = COLLATERAL_DECIMALS as usize);
+    U256::from_dec_str(&padded).context("Invalid order amount")
+}
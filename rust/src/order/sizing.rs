@@ -0,0 +1,105 @@
+use crate::config::Sizing;
+use anyhow::Result;
+
+/// What a strategy may base a BUY's size on. Balance and equity are only looked up for
+/// strategies that ask for them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SizingInput {
+    /// USDC the target spent: price × size of its trade.
+    pub target_amount: f64,
+    /// Our available USDC.
+    pub available_balance: Option<f64>,
+    /// Our USDC plus the value of our positions.
+    pub our_equity: Option<f64>,
+    /// The target's USDC plus the value of its positions, measured like `our_equity`.
+    pub target_equity: Option<f64>,
+}
+
+/// Turns a target's BUY into the USDC amount we spend on it, before the `max_order_amount`
+/// cap and the 1 USDC minimum are applied.
+pub trait SizingStrategy: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn needs_balance(&self) -> bool {
+        false
+    }
+
+    fn needs_equity(&self) -> bool {
+        false
+    }
+
+    fn amount(&self, input: &SizingInput) -> Result<f64>;
+}
+
+pub struct MultiplierSizing(pub f64);
+
+impl SizingStrategy for MultiplierSizing {
+    fn name(&self) -> &'static str {
+        "multiplier"
+    }
+
+    fn amount(&self, input: &SizingInput) -> Result<f64> {
+        Ok(input.target_amount * self.0)
+    }
+}
+
+pub struct FixedUsdSizing(pub f64);
+
+impl SizingStrategy for FixedUsdSizing {
+    fn name(&self) -> &'static str {
+        "fixed_usd"
+    }
+
+    fn amount(&self, _input: &SizingInput) -> Result<f64> {
+        Ok(self.0)
+    }
+}
+
+pub struct BalancePercentSizing(pub f64);
+
+impl SizingStrategy for BalancePercentSizing {
+    fn name(&self) -> &'static str {
+        "balance_percent"
+    }
+
+    fn needs_balance(&self) -> bool {
+        true
+    }
+
+    fn amount(&self, input: &SizingInput) -> Result<f64> {
+        match input.available_balance {
+            Some(balance) => Ok(balance.max(0.0) * self.0 / 100.0),
+            None => anyhow::bail!("available balance unknown"),
+        }
+    }
+}
+
+pub struct PortfolioProportionalSizing(pub f64);
+
+impl SizingStrategy for PortfolioProportionalSizing {
+    fn name(&self) -> &'static str {
+        "portfolio_proportional"
+    }
+
+    fn needs_equity(&self) -> bool {
+        true
+    }
+
+    fn amount(&self, input: &SizingInput) -> Result<f64> {
+        match (input.our_equity, input.target_equity) {
+            (Some(ours), Some(theirs)) if theirs > 0.0 => Ok(input.target_amount * ours.max(0.0) / theirs * self.0),
+            (_, Some(_)) => anyhow::bail!("target portfolio value is zero"),
+            _ => anyhow::bail!("portfolio values unknown"),
+        }
+    }
+}
+
+/// The strategy for a target's [`Sizing`] config.
+pub fn sizing_strategy(sizing: &Sizing) -> Box<dyn SizingStrategy> {
+    match *sizing {
+        Sizing::Multiplier { multiplier } => Box::new(MultiplierSizing(multiplier)),
+        Sizing::FixedUsd { usd } => Box::new(FixedUsdSizing(usd)),
+        Sizing::BalancePercent { percent } => Box::new(BalancePercentSizing(percent)),
+        Sizing::PortfolioProportional { multiplier } => Box::new(PortfolioProportionalSizing(multiplier)),
+    }
+}
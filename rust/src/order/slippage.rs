@@ -0,0 +1,55 @@
+use super::{simulate_market_fill, OrderSide, SimulatedFill};
+use crate::clob::OrderBook;
+use crate::config::SlippageConfig;
+
+/// Worst price a copy of a trade at `reference` may fill at: above it for BUYs and below it
+/// for SELLs by the configured allowance, rounded to a tick towards `reference` and kept
+/// within one tick of 0 and 1.
+pub fn worst_price(side: OrderSide, reference: f64, slippage: &SlippageConfig, tick_size: f64) -> f64 {
+    let allowance = slippage.allowance(reference);
+    let ticks = match side {
+        OrderSide::Buy => ((reference + allowance) / tick_size + 1e-9).floor(),
+        OrderSide::Sell => ((reference - allowance) / tick_size - 1e-9).ceil(),
+    };
+    let price = (ticks * tick_size * 1e9).round() / 1e9;
+    price.clamp(tick_size, 1.0 - tick_size)
+}
+
+/// What a market order for `amount` (USDC for BUYs, shares for SELLs) would fill in `book`
+/// without going past `worst`, or why it would not fill: nothing on the book within the
+/// price, or a FOK order that only part of the amount is available for.
+pub fn check_book(
+    book: &OrderBook,
+    side: OrderSide,
+    amount: f64,
+    worst: f64,
+    order_type: &str,
+) -> Result<SimulatedFill, String> {
+    let available = simulate_market_fill(book, side, amount, worst, "FAK");
+    let (levels, book_side) = match side {
+        OrderSide::Buy => (&book.asks, "ask"),
+        OrderSide::Sell => (&book.bids, "bid"),
+    };
+    if available.shares <= 0.0 {
+        let prices = levels.iter().filter_map(|l| l.price.parse::<f64>().ok());
+        let best = match side {
+            OrderSide::Buy => prices.reduce(f64::min),
+            OrderSide::Sell => prices.reduce(f64::max),
+        };
+        return Err(match best {
+            Some(best) => format!("best {} {} is past the worst allowed price {}", book_side, best, worst),
+            None => format!("no {}s on the book", book_side),
+        });
+    }
+    if !available.complete && order_type.eq_ignore_ascii_case("FOK") {
+        let (filled, unit) = match side {
+            OrderSide::Buy => (available.usdc, "USDC"),
+            OrderSide::Sell => (available.shares, "shares"),
+        };
+        return Err(format!(
+            "FOK for {} {} but only {:.4} fills at {} or better",
+            amount, unit, filled, worst
+        ));
+    }
+    Ok(available)
+}
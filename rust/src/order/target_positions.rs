@@ -0,0 +1,110 @@
+use crate::config::{SellMode, TargetConfig};
+use crate::data_api::get_position_size;
+use crate::feed::TradePayload;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::warn;
+
+const EPS: f64 = 1e-9;
+
+/// Running size of each target's position per token, used to turn a target's SELL into the
+/// fraction of its position it closed. A position first seen mid-way (bought before the bot
+/// started, or through trades that were missed) is seeded from the Data API `/positions`
+/// endpoint, whose size is taken to already include the trade being copied.
+#[derive(Debug, Default)]
+pub struct TargetPositions {
+    data_api_url: Option<String>,
+    /// (lowercased wallet, token id) -> shares
+    sizes: Mutex<HashMap<(String, String), f64>>,
+}
+
+impl TargetPositions {
+    /// Without a Data API URL, unknown positions are assumed to be exactly what was seen.
+    pub fn new(data_api_url: Option<String>) -> Self {
+        Self {
+            data_api_url,
+            sizes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The tracked size of `wallet`'s position in `token_id`, if any.
+    pub fn size(&self, wallet: &str, token_id: &str) -> Option<f64> {
+        self.sizes.lock().unwrap().get(&key(wallet, token_id)).copied()
+    }
+
+    /// Adds a target BUY to its position.
+    pub async fn record_buy(&self, wallet: &str, trade: &TradePayload) {
+        let key = key(wallet, &trade.asset);
+        if let Some(size) = self.sizes.lock().unwrap().get_mut(&key) {
+            *size += trade.size;
+            return;
+        }
+        let seeded = self.lookup(wallet, trade).await.unwrap_or(0.0).max(trade.size);
+        *self.sizes.lock().unwrap().entry(key).or_insert(0.0) += seeded;
+    }
+
+    /// Removes a target SELL from its position and returns the fraction of the position it
+    /// sold, in `0..=1`. A sell of a position that cannot be sized counts as a full exit.
+    pub async fn record_sell(&self, wallet: &str, trade: &TradePayload) -> f64 {
+        if trade.size <= 0.0 {
+            return 0.0;
+        }
+        let key = key(wallet, &trade.asset);
+        let known = self.sizes.lock().unwrap().get(&key).copied();
+        let before = match known {
+            Some(size) if size + EPS >= trade.size => size,
+            _ => match self.lookup(wallet, trade).await {
+                Some(after) => after + trade.size,
+                None => trade.size,
+            },
+        };
+        let remaining = before - trade.size;
+        let mut sizes = self.sizes.lock().unwrap();
+        if remaining <= EPS {
+            sizes.remove(&key);
+        } else {
+            sizes.insert(key, remaining);
+        }
+        (trade.size / before).min(1.0)
+    }
+
+    async fn lookup(&self, wallet: &str, trade: &TradePayload) -> Option<f64> {
+        let url = self.data_api_url.as_deref()?;
+        let condition_id = trade.condition_id()?;
+        match get_position_size(url, wallet, condition_id, &trade.asset).await {
+            Ok(size) => Some(size),
+            Err(e) => {
+                warn!("Could not size {}'s position in {}: {}", wallet, trade.asset, e);
+                None
+            }
+        }
+    }
+}
+
+fn key(wallet: &str, token_id: &str) -> (String, String) {
+    (wallet.to_lowercase(), token_id.to_string())
+}
+
+/// Smallest order the CLOB accepts, in shares.
+pub const MIN_ORDER_SHARES: f64 = 5.0;
+
+/// Shares to sell out of `held` when the target sold `fraction` of its position: everything
+/// in [`SellMode::Full`], else the same fraction, rounded up to everything when less than
+/// `min_remaining_shares` would be left. A partial sell below [`MIN_ORDER_SHARES`] would be
+/// rejected, so it is skipped (0); a whole position below it is still sold.
+pub fn sell_amount(held: f64, fraction: f64, target: &TargetConfig) -> f64 {
+    if fraction <= 0.0 {
+        return 0.0;
+    }
+    if target.sell_mode == SellMode::Full {
+        return held;
+    }
+    let amount = held * fraction.clamp(0.0, 1.0);
+    if held - amount < target.min_remaining_shares {
+        held
+    } else if amount < MIN_ORDER_SHARES {
+        0.0
+    } else {
+        amount
+    }
+}
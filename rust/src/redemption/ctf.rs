@@ -0,0 +1,173 @@
+use anyhow::{Context, Result};
+use ethers::abi::{ParamType, Token};
+use ethers::prelude::*;
+use ethers::types::{Address, Bytes, TransactionReceipt, H256, U256};
+
+const GET_OUTCOME_SLOT_COUNT_SELECTOR: [u8; 4] = [0xd4, 0x2d, 0xc0, 0xc2]; // getOutcomeSlotCount(bytes32)
+const PAYOUT_DENOMINATOR_SELECTOR: [u8; 4] = [0xdd, 0x34, 0xde, 0x67]; // payoutDenominator(bytes32)
+const PAYOUT_NUMERATORS_SELECTOR: [u8; 4] = [0x05, 0x04, 0xc8, 0x14]; // payoutNumerators(bytes32,uint256)
+const REDEEM_POSITIONS_SELECTOR: [u8; 4] = [0x01, 0xb7, 0x03, 0x7c]; // redeemPositions(address,bytes32,bytes32,uint256[])
+const GET_COLLECTION_ID_SELECTOR: [u8; 4] = [0x85, 0x62, 0x96, 0xf7]; // getCollectionId(bytes32,bytes32,uint256)
+const GET_POSITION_ID_SELECTOR: [u8; 4] = [0x39, 0xdd, 0x75, 0x30]; // getPositionId(address,bytes32)
+const BALANCE_OF_SELECTOR: [u8; 4] = [0x00, 0xfd, 0xd5, 0x8e]; // balanceOf(address,uint256)
+// PayoutRedemption(address,address,bytes32,bytes32,uint256[],uint256)
+const PAYOUT_REDEMPTION_TOPIC: [u8; 32] = [
+    0x26, 0x82, 0x01, 0x2a, 0x4a, 0x4f, 0x19, 0x73, 0x11, 0x9f, 0x1c, 0x9b, 0x90, 0x74, 0x5d, 0x1b, 0xd9, 0x1f, 0xa2,
+    0xba, 0xb3, 0x87, 0x34, 0x4f, 0x04, 0x4c, 0xb3, 0x58, 0x68, 0x64, 0xd1, 0x8d,
+];
+
+#[derive(Debug, Clone)]
+pub struct ConditionResolution {
+    pub is_resolved: bool,
+    pub outcome_slot_count: u64,
+    pub payout_denominator: U256,
+    pub payout_numerators: Vec<U256>,
+    /// 1-based index sets (`1 << outcome_index`) whose payout numerator is non-zero.
+    pub winning_index_sets: Vec<u64>,
+}
+
+/// Accepts a `0x` hex condition id (left-padded to 32 bytes like `hexZeroPad`) or a decimal string.
+pub fn condition_id_bytes32(condition_id: &str) -> Result<[u8; 32]> {
+    let mut out = [0u8; 32];
+    if let Some(h) = condition_id.strip_prefix("0x") {
+        let h = if h.len() % 2 == 1 { format!("0{}", h) } else { h.to_string() };
+        let bytes = hex::decode(&h).context("Invalid condition id hex")?;
+        if bytes.len() > 32 {
+            anyhow::bail!("Condition id longer than 32 bytes");
+        }
+        out[32 - bytes.len()..].copy_from_slice(&bytes);
+    } else {
+        U256::from_dec_str(condition_id)
+            .context("Invalid condition id")?
+            .to_big_endian(&mut out);
+    }
+    Ok(out)
+}
+
+pub fn encode_redeem_positions(collateral: Address, condition_id: [u8; 32], index_sets: &[u64]) -> Bytes {
+    let mut data = Vec::from(REDEEM_POSITIONS_SELECTOR);
+    data.extend_from_slice(&ethers::abi::encode(&[
+        Token::Address(collateral),
+        Token::FixedBytes(vec![0u8; 32]),
+        Token::FixedBytes(condition_id.to_vec()),
+        Token::Array(index_sets.iter().map(|i| Token::Uint(U256::from(*i))).collect()),
+    ]));
+    Bytes::from(data)
+}
+
+pub async fn check_condition_resolution(
+    provider: &Provider<Http>,
+    ctf: Address,
+    condition_id: [u8; 32],
+) -> Result<ConditionResolution> {
+    let cid = Token::FixedBytes(condition_id.to_vec());
+    let outcome_slot_count = call_uint(provider, ctf, GET_OUTCOME_SLOT_COUNT_SELECTOR, std::slice::from_ref(&cid))
+        .await
+        .context("getOutcomeSlotCount call")?
+        .as_u64();
+    let payout_denominator = call_uint(provider, ctf, PAYOUT_DENOMINATOR_SELECTOR, std::slice::from_ref(&cid))
+        .await
+        .context("payoutDenominator call")?;
+    let is_resolved = !payout_denominator.is_zero();
+
+    let mut payout_numerators = Vec::new();
+    let mut winning_index_sets = Vec::new();
+    if is_resolved {
+        for i in 0..outcome_slot_count {
+            let n = call_uint(provider, ctf, PAYOUT_NUMERATORS_SELECTOR, &[cid.clone(), Token::Uint(U256::from(i))])
+                .await
+                .context("payoutNumerators call")?;
+            if !n.is_zero() {
+                winning_index_sets.push(1u64 << i);
+            }
+            payout_numerators.push(n);
+        }
+    }
+    Ok(ConditionResolution {
+        is_resolved,
+        outcome_slot_count,
+        payout_denominator,
+        payout_numerators,
+        winning_index_sets,
+    })
+}
+
+/// ERC-1155 balance of `owner` for the outcome position `index_set` of a condition.
+pub async fn position_balance(
+    provider: &Provider<Http>,
+    ctf: Address,
+    collateral: Address,
+    owner: Address,
+    condition_id: [u8; 32],
+    index_set: u64,
+) -> Result<U256> {
+    let position_id = position_id(provider, ctf, collateral, condition_id, index_set).await?;
+    call_uint(provider, ctf, BALANCE_OF_SELECTOR, &[Token::Address(owner), Token::Uint(position_id)])
+        .await
+        .context("balanceOf call")
+}
+
+/// ERC-1155 token id of the outcome position `index_set` of a condition: the CLOB token id.
+pub async fn position_id(
+    provider: &Provider<Http>,
+    ctf: Address,
+    collateral: Address,
+    condition_id: [u8; 32],
+    index_set: u64,
+) -> Result<U256> {
+    let collection_id = call_word(
+        provider,
+        ctf,
+        GET_COLLECTION_ID_SELECTOR,
+        &[
+            Token::FixedBytes(vec![0u8; 32]),
+            Token::FixedBytes(condition_id.to_vec()),
+            Token::Uint(U256::from(index_set)),
+        ],
+    )
+    .await
+    .context("getCollectionId call")?;
+    call_uint(
+        provider,
+        ctf,
+        GET_POSITION_ID_SELECTOR,
+        &[Token::Address(collateral), Token::FixedBytes(collection_id.to_vec())],
+    )
+    .await
+    .context("getPositionId call")
+}
+
+/// Sums `payout` from the CTF `PayoutRedemption` events in a receipt (6-decimal collateral units).
+pub fn payout_from_receipt(receipt: &TransactionReceipt, ctf: Address) -> U256 {
+    let topic = H256::from(PAYOUT_REDEMPTION_TOPIC);
+    receipt
+        .logs
+        .iter()
+        .filter(|l| l.address == ctf && l.topics.first() == Some(&topic))
+        .filter_map(|l| {
+            ethers::abi::decode(
+                &[
+                    ParamType::FixedBytes(32),
+                    ParamType::Array(Box::new(ParamType::Uint(256))),
+                    ParamType::Uint(256),
+                ],
+                &l.data,
+            )
+            .ok()
+        })
+        .filter_map(|tokens| tokens.into_iter().nth(2).and_then(|t| t.into_uint()))
+        .fold(U256::zero(), |acc, p| acc + p)
+}
+
+async fn call_uint(provider: &Provider<Http>, to: Address, selector: [u8; 4], args: &[Token]) -> Result<U256> {
+    Ok(U256::from_big_endian(&call_word(provider, to, selector, args).await?))
+}
+
+pub(super) async fn call_word(provider: &Provider<Http>, to: Address, selector: [u8; 4], args: &[Token]) -> Result<[u8; 32]> {
+    let mut data = Vec::from(selector);
+    data.extend_from_slice(&ethers::abi::encode(args));
+    let tx = TransactionRequest::default().to(to).data(Bytes::from(data));
+    let res = provider.call(&tx.into(), None).await?;
+    let out: [u8; 32] = res.as_ref().try_into().context("call result length")?;
+    Ok(out)
+}
@@ -1,26 +1,410 @@
-use anyhow::Result;
+use crate::chain::{address_from_hex, approve_neg_risk_adapter, get_contract_config, ContractConfig};
+use crate::config::Config;
+use crate::risk::RiskManager;
+use anyhow::{Context, Result};
+use ethers::prelude::*;
+use std::future::Future;
 use std::path::Path;
-use tracing::info;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{info, warn};
 
+mod ctf;
+mod neg_risk;
+
+pub use ctf::{
+    check_condition_resolution, condition_id_bytes32, encode_redeem_positions, position_balance, position_id,
+    ConditionResolution,
+};
+pub use neg_risk::encode_neg_risk_redeem_positions;
+
+const REDEEM_GAS_LIMIT: u64 = 500_000;
+const RETRY_INITIAL_DELAY: Duration = Duration::from_secs(2);
+
+/// Chain and API settings shared by the redemption entry points.
+#[derive(Debug, Clone)]
+pub struct RedeemConfig {
+    pub chain_id: u64,
+    pub private_key: String,
+    pub rpc_url: String,
+    pub clob_api_url: String,
+    /// Neg-risk flag used when the CLOB market lookup fails.
+    pub neg_risk: bool,
+}
+
+impl RedeemConfig {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            chain_id: config.chain_id,
+            private_key: config.private_key.clone(),
+            rpc_url: config.rpc_url.clone(),
+            clob_api_url: config.clob_api_url.clone(),
+            neg_risk: config.neg_risk,
+        }
+    }
+}
+
+/// Which contract a condition is redeemed through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedeemRoute {
+    /// `ConditionalTokens.redeemPositions(collateral, parentCollectionId, conditionId, indexSets)`
+    Ctf,
+    /// `NegRiskAdapter.redeemPositions(conditionId, amounts)`
+    NegRiskAdapter,
+}
+
+#[derive(Debug, Clone)]
+pub struct RedeemOutcome {
+    pub condition_id: String,
+    pub route: RedeemRoute,
+    pub index_sets: Vec<u64>,
+    pub tx_hash: String,
+    pub usdc_received: f64,
+    /// CLOB token ids of every winning outcome, which the payout is attributed to.
+    pub winning_token_ids: Vec<String>,
+}
+
+/// Neg-risk markets go through the adapter. Uses CLOB market metadata, falling back to
+/// `RedeemConfig::neg_risk` if the lookup fails.
+pub async fn detect_route(rc: &RedeemConfig, condition_id: &str) -> RedeemRoute {
+    let neg_risk = match crate::clob::get_market(&rc.clob_api_url, condition_id).await {
+        Ok(m) => m.neg_risk,
+        Err(e) => {
+            warn!("Market lookup failed for {} ({}); using NEG_RISK={}", condition_id, e, rc.neg_risk);
+            rc.neg_risk
+        }
+    };
+    if neg_risk {
+        RedeemRoute::NegRiskAdapter
+    } else {
+        RedeemRoute::Ctf
+    }
+}
+
+/// Target contract and calldata redeeming `index_sets` of a condition. The adapter takes
+/// per-outcome amounts instead: `outcome_balances[i]` for winning sets, 0 for the rest.
+pub fn redeem_call(
+    route: RedeemRoute,
+    cfg: &ContractConfig,
+    condition_id: [u8; 32],
+    index_sets: &[u64],
+    outcome_balances: &[U256],
+) -> Result<(Address, Bytes)> {
+    match route {
+        RedeemRoute::Ctf => Ok((
+            address_from_hex(&cfg.conditional_tokens)?,
+            encode_redeem_positions(address_from_hex(&cfg.collateral)?, condition_id, index_sets),
+        )),
+        RedeemRoute::NegRiskAdapter => {
+            if outcome_balances.is_empty() {
+                anyhow::bail!("Neg-risk redemption needs outcome balances");
+            }
+            let amounts: Vec<U256> = outcome_balances
+                .iter()
+                .enumerate()
+                .map(|(i, b)| if index_sets.contains(&(1u64 << i)) { *b } else { U256::zero() })
+                .collect();
+            Ok((
+                address_from_hex(&cfg.neg_risk_adapter)?,
+                encode_neg_risk_redeem_positions(condition_id, &amounts),
+            ))
+        }
+    }
+}
+
+/// Collateral the outcome positions of `route` are minted against (USDC, or the adapter's
+/// WrappedCollateral for neg-risk markets).
+async fn position_collateral(provider: &Provider<Http>, route: RedeemRoute, cfg: &ContractConfig) -> Result<Address> {
+    match route {
+        RedeemRoute::Ctf => address_from_hex(&cfg.collateral),
+        RedeemRoute::NegRiskAdapter => neg_risk::wrapped_collateral(provider, address_from_hex(&cfg.neg_risk_adapter)?).await,
+    }
+}
+
+async fn outcome_balances(
+    provider: &Provider<Http>,
+    ctf: Address,
+    collateral: Address,
+    owner: Address,
+    condition_id: [u8; 32],
+    outcome_slot_count: u64,
+) -> Result<Vec<U256>> {
+    let mut balances = Vec::with_capacity(outcome_slot_count as usize);
+    for i in 0..outcome_slot_count {
+        balances.push(position_balance(provider, ctf, collateral, owner, condition_id, 1u64 << i).await?);
+    }
+    Ok(balances)
+}
+
+/// A redeem transaction that was broadcast but whose receipt could not be fetched. It may
+/// still be mined, so it is never retried by sending again.
+#[derive(Debug, thiserror::Error)]
+#[error("redeemPositions {tx_hash} sent but not confirmed")]
+pub struct RedeemUnconfirmed {
+    pub tx_hash: String,
+}
+
+/// The RPC has no receipt for a sent transaction yet; looked up again by [`retry_with_backoff`].
+#[derive(Debug, thiserror::Error)]
+#[error("receipt not available yet")]
+struct ReceiptPending;
+
+/// Everything checked and encoded before a redeem transaction is sent.
+struct PreparedRedeem {
+    route: RedeemRoute,
+    index_sets: Vec<u64>,
+    winning_token_ids: Vec<String>,
+    ctf: Address,
+    to: Address,
+    data: Bytes,
+}
+
+/// Redeems `index_sets` (default: every winning set) of a resolved condition through the CTF,
+/// or the NegRiskAdapter for neg-risk markets. Requested sets that did not win, or that the
+/// wallet holds none of, are dropped; fails if nothing redeemable is left.
 pub async fn redeem_positions(
-    _condition_id: &str,
-    _index_sets: Option<Vec<u64>>,
-    _chain_id: u64,
-    _private_key: &str,
-    _rpc_url: &str,
-) -> Result<()> {
-    info!("Redeem positions: CTF redeem not yet implemented in Rust - use TS redeem script");
-    Ok(())
+    condition_id: &str,
+    index_sets: Option<Vec<u64>>,
+    rc: &RedeemConfig,
+) -> Result<RedeemOutcome> {
+    let provider = Provider::<Http>::try_from(rc.rpc_url.as_str())?;
+    let ctf = address_from_hex(&get_contract_config(rc.chain_id).conditional_tokens)?;
+    let resolution = check_condition_resolution(&provider, ctf, condition_id_bytes32(condition_id)?).await?;
+    match prepare_redeem(condition_id, index_sets, &resolution, rc).await? {
+        Some(prepared) => send_redeem(condition_id, prepared, rc, 1).await,
+        None => anyhow::bail!("No winning tokens of {} held; nothing to redeem", condition_id),
+    }
+}
+
+/// The on-chain reads (route, balances) and neg-risk approval before redeeming a condition
+/// whose `resolution` was already fetched. `None` if the wallet holds none of the winning sets.
+async fn prepare_redeem(
+    condition_id: &str,
+    index_sets: Option<Vec<u64>>,
+    resolution: &ConditionResolution,
+    rc: &RedeemConfig,
+) -> Result<Option<PreparedRedeem>> {
+    let provider = Provider::<Http>::try_from(rc.rpc_url.as_str())?;
+    let wallet =
+        LocalWallet::from_bytes(&hex::decode(rc.private_key.trim_start_matches("0x"))?)?.with_chain_id(rc.chain_id);
+    let cfg = get_contract_config(rc.chain_id);
+    let ctf = address_from_hex(&cfg.conditional_tokens)?;
+    let cid = condition_id_bytes32(condition_id)?;
+
+    if !resolution.is_resolved {
+        anyhow::bail!("Market is not yet resolved: {}", condition_id);
+    }
+    if resolution.winning_index_sets.is_empty() {
+        anyhow::bail!("Condition is resolved but no winning outcomes found");
+    }
+    let sets = winning_subset(index_sets, &resolution.winning_index_sets);
+    if sets.is_empty() {
+        anyhow::bail!(
+            "None of the requested index sets won. Winners: {:?}",
+            resolution.winning_index_sets
+        );
+    }
+
+    let route = detect_route(rc, condition_id).await;
+    let collateral = position_collateral(&provider, route, &cfg).await?;
+    let mut winning_token_ids = Vec::with_capacity(resolution.winning_index_sets.len());
+    for index_set in &resolution.winning_index_sets {
+        winning_token_ids.push(position_id(&provider, ctf, collateral, cid, *index_set).await?.to_string());
+    }
+    let balances =
+        outcome_balances(&provider, ctf, collateral, wallet.address(), cid, resolution.outcome_slot_count).await?;
+    let held: Vec<u64> = sets
+        .into_iter()
+        .filter(|s| balances.get(s.trailing_zeros() as usize).is_some_and(|b| !b.is_zero()))
+        .collect();
+    if held.is_empty() {
+        return Ok(None);
+    }
+    if route == RedeemRoute::NegRiskAdapter {
+        approve_neg_risk_adapter(&provider, &wallet, rc.chain_id).await?;
+    }
+    let (to, data) = redeem_call(route, &cfg, cid, &held, &balances)?;
+    Ok(Some(PreparedRedeem { route, index_sets: held, winning_token_ids, ctf, to, data }))
+}
+
+/// Sends the redeem transaction once and waits for its receipt. If waiting fails, the
+/// receipt is looked up by hash (up to `max_retries` times) instead of sending again.
+async fn send_redeem(
+    condition_id: &str,
+    prepared: PreparedRedeem,
+    rc: &RedeemConfig,
+    max_retries: u32,
+) -> Result<RedeemOutcome> {
+    let provider = Provider::<Http>::try_from(rc.rpc_url.as_str())?;
+    let wallet =
+        LocalWallet::from_bytes(&hex::decode(rc.private_key.trim_start_matches("0x"))?)?.with_chain_id(rc.chain_id);
+    let PreparedRedeem { route, index_sets: sets, winning_token_ids, ctf, to, data } = prepared;
+
+    info!("=== REDEEMING POSITIONS ===");
+    info!("Condition ID: {}", condition_id);
+    info!("Route: {:?}", route);
+    info!("Index Sets: {:?}", sets);
+    info!("Wallet: {:?}", wallet.address());
+
+    let gas_price = provider.get_gas_price().await.unwrap_or(U256::from(100_000_000_000u64));
+    let client = SignerMiddleware::new(provider.clone(), wallet);
+    let tx = TransactionRequest::default()
+        .to(to)
+        .data(data)
+        .gas(REDEEM_GAS_LIMIT)
+        .gas_price(gas_price * 120 / 100);
+    let pending = client.send_transaction(tx, None).await.context("redeemPositions send")?;
+    let hash = pending.tx_hash();
+    let tx_hash = format!("{:?}", hash);
+    info!("Transaction sent: {}", tx_hash);
+    let receipt = match pending.await {
+        Ok(Some(receipt)) => receipt,
+        Ok(None) => anyhow::bail!("redeemPositions transaction dropped: {}", tx_hash),
+        Err(e) => {
+            warn!("Waiting for {} failed ({}); looking the receipt up", tx_hash, e);
+            retry_with_backoff(
+                || async {
+                    provider
+                        .get_transaction_receipt(hash)
+                        .await
+                        .context("rpc: get transaction receipt")?
+                        .ok_or_else(|| anyhow::Error::new(ReceiptPending))
+                },
+                max_retries,
+                RETRY_INITIAL_DELAY,
+            )
+            .await
+            .context(RedeemUnconfirmed { tx_hash: tx_hash.clone() })?
+        }
+    };
+    if receipt.status != Some(U64::from(1)) {
+        anyhow::bail!("redeemPositions reverted: {}", tx_hash);
+    }
+
+    let payout = match route {
+        RedeemRoute::Ctf => ctf::payout_from_receipt(&receipt, ctf),
+        RedeemRoute::NegRiskAdapter => neg_risk::payout_from_receipt(&receipt, to),
+    };
+    let usdc_received = payout.as_u128() as f64 / 1e6;
+    info!(
+        "Redeemed in block {:?}, gas used {:?}, USDC received: {:.6}",
+        receipt.block_number, receipt.gas_used, usdc_received
+    );
+    Ok(RedeemOutcome {
+        condition_id: condition_id.to_string(),
+        route,
+        index_sets: sets,
+        tx_hash,
+        usdc_received,
+        winning_token_ids,
+    })
 }
 
+/// Redeems every winning index set the wallet holds of a market with a known `resolution`;
+/// `None` (and nothing sent) if it holds no winning tokens. The on-chain reads before sending
+/// are retried on RPC/network errors with exponential backoff (2s, 4s, 8s, ...) up to
+/// `max_retries` attempts; the transaction itself is sent only once.
 pub async fn redeem_market(
-    _condition_id: &str,
-    _chain_id: u64,
-    _private_key: &str,
-    _rpc_url: &str,
-    _max_retries: u32,
-) -> Result<()> {
-    info!("Redeem market: use TS redeem script");
+    condition_id: &str,
+    resolution: &ConditionResolution,
+    rc: &RedeemConfig,
+    max_retries: u32,
+) -> Result<Option<RedeemOutcome>> {
+    let prepared = retry_with_backoff(
+        || prepare_redeem(condition_id, None, resolution, rc),
+        max_retries,
+        RETRY_INITIAL_DELAY,
+    )
+    .await?;
+    match prepared {
+        Some(prepared) => send_redeem(condition_id, prepared, rc, max_retries).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// What a redemption would do, computed without sending a transaction.
+#[derive(Debug, Clone)]
+pub struct RedeemPlan {
+    pub condition_id: String,
+    pub route: RedeemRoute,
+    pub is_resolved: bool,
+    pub winning_index_sets: Vec<u64>,
+    /// Winning index sets the wallet holds a non-zero balance of.
+    pub held_winning_index_sets: Vec<u64>,
+    pub expected_payout: f64,
+    pub estimated_gas: Option<U256>,
+    pub gas_price: U256,
+    pub error: Option<String>,
+}
+
+impl RedeemPlan {
+    /// Estimated transaction cost in POL (gas * gas price, 18 decimals).
+    pub fn estimated_cost(&self) -> Option<f64> {
+        self.estimated_gas
+            .map(|g| (g * self.gas_price).as_u128() as f64 / 1e18)
+    }
+}
+
+/// Dry run of [`auto_redeem_markets`]: route, resolution, winning sets held, expected USDC
+/// payout and gas estimate per market.
+pub async fn plan_redemptions(condition_ids: &[String], rc: &RedeemConfig) -> Result<Vec<RedeemPlan>> {
+    let provider = Provider::<Http>::try_from(rc.rpc_url.as_str())?;
+    let wallet = LocalWallet::from_bytes(&hex::decode(rc.private_key.trim_start_matches("0x"))?)?;
+    let gas_price = provider.get_gas_price().await.unwrap_or(U256::from(100_000_000_000u64)) * 120 / 100;
+
+    let mut plans = Vec::with_capacity(condition_ids.len());
+    for condition_id in condition_ids {
+        let mut plan = RedeemPlan {
+            condition_id: condition_id.clone(),
+            route: RedeemRoute::Ctf,
+            is_resolved: false,
+            winning_index_sets: Vec::new(),
+            held_winning_index_sets: Vec::new(),
+            expected_payout: 0.0,
+            estimated_gas: None,
+            gas_price,
+            error: None,
+        };
+        if let Err(e) = fill_plan(&mut plan, &provider, rc, wallet.address()).await {
+            plan.error = Some(format!("{:#}", e));
+        }
+        plans.push(plan);
+    }
+    Ok(plans)
+}
+
+async fn fill_plan(plan: &mut RedeemPlan, provider: &Provider<Http>, rc: &RedeemConfig, owner: Address) -> Result<()> {
+    let cfg = get_contract_config(rc.chain_id);
+    let ctf = address_from_hex(&cfg.conditional_tokens)?;
+    let cid = condition_id_bytes32(&plan.condition_id)?;
+    let resolution = check_condition_resolution(provider, ctf, cid).await?;
+    plan.is_resolved = resolution.is_resolved;
+    plan.winning_index_sets = resolution.winning_index_sets.clone();
+    if !resolution.is_resolved {
+        return Ok(());
+    }
+
+    plan.route = detect_route(rc, &plan.condition_id).await;
+    let collateral = position_collateral(provider, plan.route, &cfg).await?;
+    let balances = outcome_balances(provider, ctf, collateral, owner, cid, resolution.outcome_slot_count).await?;
+    let mut payout = U256::zero();
+    for (i, (numerator, balance)) in resolution.payout_numerators.iter().zip(&balances).enumerate() {
+        if !numerator.is_zero() && !balance.is_zero() {
+            plan.held_winning_index_sets.push(1u64 << i);
+            payout += *balance * *numerator / resolution.payout_denominator;
+        }
+    }
+    plan.expected_payout = payout.as_u128() as f64 / 1e6;
+
+    if !plan.winning_index_sets.is_empty() {
+        let (to, data) = redeem_call(plan.route, &cfg, cid, &plan.winning_index_sets, &balances)?;
+        let tx: ethers::types::transaction::eip2718::TypedTransaction =
+            TransactionRequest::default().from(owner).to(to).data(data).into();
+        match provider.estimate_gas(&tx, None).await {
+            Ok(g) => plan.estimated_gas = Some(g),
+            Err(e) => warn!("Gas estimate failed for {}: {}", plan.condition_id, e),
+        }
+    }
     Ok(())
 }
 
@@ -36,32 +420,208 @@ pub struct MarketRedeemResult {
     pub condition_id: String,
     pub is_resolved: bool,
     pub redeemed: bool,
+    pub tx_hash: Option<String>,
+    pub usdc_received: Option<f64>,
     pub error: Option<String>,
 }
 
+/// Checks every market in the holdings file on-chain, redeems the resolved ones
+/// (retrying with backoff up to `max_retries`) and clears their holdings. `risk` starts the
+/// day's loss baseline before a payout is booked, so a losing resolution counts towards today.
+/// Holdings and target ledgers are only written while holding `ledger_lock`, the lock the
+/// order builder updates them under ([`crate::TradeOrderBuilder::ledger_lock`]).
 pub async fn auto_redeem_resolved_markets(
-    _holdings_path: &Path,
-    _chain_id: u64,
-    _private_key: &str,
-    _rpc_url: &str,
-    _max_retries: u32,
+    holdings_path: &Path,
+    rc: &RedeemConfig,
+    max_retries: u32,
+    risk: &RiskManager,
+    ledger_lock: &Mutex<()>,
 ) -> Result<AutoRedeemSummary> {
-    let holdings = crate::holdings::get_all_holdings(_holdings_path);
-    let total = holdings.len();
-    info!("Auto-redeem: {} markets in holdings (Rust redemption not yet implemented)", total);
-    Ok(AutoRedeemSummary {
+    let condition_ids: Vec<String> = crate::holdings::get_all_holdings(holdings_path).into_keys().collect();
+    auto_redeem_markets(&condition_ids, holdings_path, rc, max_retries, risk, ledger_lock).await
+}
+
+/// Same as [`auto_redeem_resolved_markets`] for an explicit list of markets (e.g. from the
+/// Data API). Markets that are also in the holdings file are cleared after redemption.
+pub async fn auto_redeem_markets(
+    condition_ids: &[String],
+    holdings_path: &Path,
+    rc: &RedeemConfig,
+    max_retries: u32,
+    risk: &RiskManager,
+    ledger_lock: &Mutex<()>,
+) -> Result<AutoRedeemSummary> {
+    let provider = Provider::<Http>::try_from(rc.rpc_url.as_str())?;
+    let ctf = address_from_hex(&get_contract_config(rc.chain_id).conditional_tokens)?;
+    let total = condition_ids.len();
+    info!("=== AUTO-REDEEM: Checking {} markets ===", total);
+
+    let mut summary = AutoRedeemSummary {
         total,
         resolved: 0,
         redeemed: 0,
         failed: 0,
-        results: holdings
-            .keys()
-            .map(|k| MarketRedeemResult {
-                condition_id: k.clone(),
-                is_resolved: false,
-                redeemed: false,
-                error: Some("Use TS auto-redeem script".to_string()),
-            })
-            .collect(),
+        results: Vec::with_capacity(total),
+    };
+    for condition_id in condition_ids {
+        let mut result = MarketRedeemResult {
+            condition_id: condition_id.clone(),
+            is_resolved: false,
+            redeemed: false,
+            tx_hash: None,
+            usdc_received: None,
+            error: None,
+        };
+        let resolution = match condition_id_bytes32(condition_id) {
+            Ok(cid) => {
+                retry_with_backoff(
+                    || check_condition_resolution(&provider, ctf, cid),
+                    max_retries,
+                    RETRY_INITIAL_DELAY,
+                )
+                .await
+            }
+            Err(e) => Err(e),
+        };
+        match resolution {
+            Ok(r) if r.is_resolved => {
+                summary.resolved += 1;
+                result.is_resolved = true;
+                info!("Redeeming resolved market: {}", condition_id);
+                match redeem_market(condition_id, &r, rc, max_retries).await {
+                    Ok(Some(outcome)) => {
+                        summary.redeemed += 1;
+                        result.redeemed = true;
+                        result.tx_hash = Some(outcome.tx_hash);
+                        result.usdc_received = Some(outcome.usdc_received);
+                        close_redeemed_market(
+                            holdings_path,
+                            risk,
+                            ledger_lock,
+                            condition_id,
+                            outcome.usdc_received,
+                            &outcome.winning_token_ids,
+                        );
+                    }
+                    Ok(None) => {
+                        // Only losing tokens (or none) left: nothing to send, the position is worth 0.
+                        info!("No winning tokens of {} held; skipping redemption", condition_id);
+                        result.error = Some("No winning tokens held".to_string());
+                        close_redeemed_market(holdings_path, risk, ledger_lock, condition_id, 0.0, &[]);
+                    }
+                    Err(e) => {
+                        summary.failed += 1;
+                        warn!("Failed to redeem {}: {:#}", condition_id, e);
+                        result.error = Some(format!("{:#}", e));
+                    }
+                }
+            }
+            Ok(_) => {
+                info!("Market {} not resolved", condition_id);
+                result.error = Some("Condition not yet resolved".to_string());
+            }
+            Err(e) => {
+                summary.failed += 1;
+                warn!("Error checking resolution for {}: {:#}", condition_id, e);
+                result.error = Some(format!("Error checking resolution: {:#}", e));
+            }
+        }
+        summary.results.push(result);
+    }
+
+    info!("=== AUTO-REDEEM SUMMARY ===");
+    info!(
+        "Total: {} Resolved: {} Redeemed: {} Failed: {}",
+        summary.total, summary.resolved, summary.redeemed, summary.failed
+    );
+    Ok(summary)
+}
+
+/// Clears a resolved market from the holdings file and the target ledger, booking `payout`.
+fn close_redeemed_market(
+    holdings_path: &Path,
+    risk: &RiskManager,
+    ledger_lock: &Mutex<()>,
+    condition_id: &str,
+    payout: f64,
+    winning_token_ids: &[String],
+) {
+    let _guard = ledger_lock.lock().unwrap();
+    if crate::holdings::get_all_holdings(holdings_path).contains_key(condition_id) {
+        if let Err(e) = crate::holdings::clear_market_holdings(holdings_path, condition_id) {
+            warn!("Failed to clear holdings for {}: {}", condition_id, e);
+        }
+    }
+    if let Err(e) = risk.roll_day() {
+        warn!("Failed to start the daily loss baseline: {}", e);
+    }
+    let ledger_path = crate::holdings::target_ledger_path(holdings_path);
+    if let Err(e) = crate::holdings::settle_target_market(&ledger_path, condition_id, payout, winning_token_ids) {
+        warn!("Failed to settle target ledger for {}: {}", condition_id, e);
+    }
+}
+
+fn winning_subset(requested: Option<Vec<u64>>, winning: &[u64]) -> Vec<u64> {
+    match requested {
+        Some(sets) => sets.into_iter().filter(|s| winning.contains(s)).collect(),
+        None => winning.to_vec(),
+    }
+}
+
+/// JSON-RPC error codes for a node that is overloaded or rate limiting us.
+const RETRYABLE_RPC_CODES: [i64; 3] = [-32005, -32603, 429];
+
+/// Whether a failed redemption step may be run again: the RPC could not be reached, timed
+/// out, answered 429/5xx or something that is not JSON-RPC (a gateway error page), reported
+/// rate limiting, or has no receipt yet. Never a transaction that was already sent
+/// ([`RedeemUnconfirmed`]).
+pub fn is_retryable(e: &anyhow::Error) -> bool {
+    if e.downcast_ref::<RedeemUnconfirmed>().is_some() {
+        return false;
+    }
+    e.chain().any(|cause| {
+        if cause.is::<ReceiptPending>() {
+            return true;
+        }
+        if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+            return is_transient_http(err);
+        }
+        match cause.downcast_ref::<ProviderError>() {
+            Some(ProviderError::HTTPError(err)) => is_transient_http(err),
+            Some(ProviderError::JsonRpcClientError(err)) => match err.as_error_response() {
+                Some(response) => RETRYABLE_RPC_CODES.contains(&response.code),
+                None => err.as_serde_error().is_some(),
+            },
+            _ => false,
+        }
     })
 }
+
+fn is_transient_http(err: &reqwest::Error) -> bool {
+    err.is_timeout()
+        || err.is_connect()
+        || err
+            .status()
+            .is_some_and(|s| s.is_server_error() || s == reqwest::StatusCode::TOO_MANY_REQUESTS)
+}
+
+async fn retry_with_backoff<T, F, Fut>(mut f: F, max_retries: u32, initial_delay: Duration) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let max_retries = max_retries.max(1);
+    let mut attempt = 1;
+    loop {
+        match f().await {
+            Ok(v) => return Ok(v),
+            Err(e) if attempt < max_retries && is_retryable(&e) => {
+                let delay = initial_delay * 2u32.pow(attempt - 1);
+                warn!("Attempt {}/{} failed: {:#}. Retrying in {:?}...", attempt, max_retries, e, delay);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use super::ctf::call_word;
+use anyhow::{Context, Result};
+use ethers::abi::{ParamType, Token};
+use ethers::prelude::*;
+use ethers::types::{Address, Bytes, TransactionReceipt, H256, U256};
+
+const REDEEM_POSITIONS_SELECTOR: [u8; 4] = [0xdb, 0xec, 0xcb, 0x23]; // redeemPositions(bytes32,uint256[])
+const WCOL_SELECTOR: [u8; 4] = [0x7e, 0x3b, 0x74, 0xc3]; // wcol()
+// PayoutRedemption(address,bytes32,uint256[],uint256)
+const PAYOUT_REDEMPTION_TOPIC: [u8; 32] = [
+    0x91, 0x40, 0xa6, 0xa2, 0x70, 0xef, 0x94, 0x52, 0x60, 0xc0, 0x38, 0x94, 0xb3, 0xc6, 0xb3, 0xb2, 0x69, 0x5e, 0x9d,
+    0x51, 0x01, 0xfe, 0xef, 0x0f, 0xf2, 0x4f, 0xec, 0x96, 0x0c, 0xfd, 0x32, 0x24,
+];
+
+/// NegRiskAdapter `redeemPositions(conditionId, amounts)`; `amounts[i]` is the number of
+/// outcome-`i` tokens to redeem.
+pub fn encode_neg_risk_redeem_positions(condition_id: [u8; 32], amounts: &[U256]) -> Bytes {
+    let mut data = Vec::from(REDEEM_POSITIONS_SELECTOR);
+    data.extend_from_slice(&ethers::abi::encode(&[
+        Token::FixedBytes(condition_id.to_vec()),
+        Token::Array(amounts.iter().map(|a| Token::Uint(*a)).collect()),
+    ]));
+    Bytes::from(data)
+}
+
+/// WrappedCollateral used by the adapter as the CTF collateral of neg-risk positions.
+pub async fn wrapped_collateral(provider: &Provider<Http>, adapter: Address) -> Result<Address> {
+    let word = call_word(provider, adapter, WCOL_SELECTOR, &[]).await.context("wcol call")?;
+    Ok(Address::from_slice(&word[12..]))
+}
+
+/// Sums `payout` from the adapter `PayoutRedemption` events in a receipt.
+pub fn payout_from_receipt(receipt: &TransactionReceipt, adapter: Address) -> U256 {
+    let topic = H256::from(PAYOUT_REDEMPTION_TOPIC);
+    receipt
+        .logs
+        .iter()
+        .filter(|l| l.address == adapter && l.topics.first() == Some(&topic))
+        .filter_map(|l| {
+            ethers::abi::decode(&[ParamType::Array(Box::new(ParamType::Uint(256))), ParamType::Uint(256)], &l.data).ok()
+        })
+        .filter_map(|tokens| tokens.into_iter().nth(1).and_then(|t| t.into_uint()))
+        .fold(U256::zero(), |acc, p| acc + p)
+}
@@ -0,0 +1,271 @@
+use crate::config::RiskConfig;
+use crate::holdings::{get_target_ledgers, target_ledger_path};
+use crate::order::{load_resting_orders, resting_orders_path, OrderSide, RestingOrder};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::error;
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// A risk limit that stopped a copied BUY.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskViolation {
+    /// Realized PnL since the start of the UTC day is at or below `-limit`; buys are halted
+    /// until the next day.
+    DailyLossLimit { realized: f64, limit: f64 },
+    MarketExposure { market: String, current: f64, order: f64, limit: f64 },
+    TotalExposure { current: f64, order: f64, limit: f64 },
+    OpenPositions { open: usize, limit: usize },
+    DailySpend { spent: f64, order: f64, limit: f64 },
+}
+
+impl RiskViolation {
+    /// Stable identifier of the limit, e.g. for alerting on `CopyTradeResult.error`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::DailyLossLimit { .. } => "max_daily_loss",
+            Self::MarketExposure { .. } => "max_market_exposure",
+            Self::TotalExposure { .. } => "max_total_exposure",
+            Self::OpenPositions { .. } => "max_open_positions",
+            Self::DailySpend { .. } => "max_daily_spend",
+        }
+    }
+}
+
+impl fmt::Display for RiskViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "risk limit {}: ", self.code())?;
+        match self {
+            Self::DailyLossLimit { realized, limit } => {
+                write!(f, "realized {:.2} USDC today, limit -{:.2}; copying halted until 00:00 UTC", realized, limit)
+            }
+            Self::MarketExposure { market, current, order, limit } => write!(
+                f,
+                "{:.2} + {:.2} USDC in market {} would exceed {:.2}",
+                current, order, market, limit
+            ),
+            Self::TotalExposure { current, order, limit } => {
+                write!(f, "{:.2} + {:.2} USDC total would exceed {:.2}", current, order, limit)
+            }
+            Self::OpenPositions { open, limit } => write!(f, "{} positions open, limit {}", open, limit),
+            Self::DailySpend { spent, order, limit } => {
+                write!(f, "{:.2} + {:.2} USDC spent in 24h would exceed {:.2}", spent, order, limit)
+            }
+        }
+    }
+}
+
+/// Persisted between runs in `risk-state.json` next to the holdings file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RiskState {
+    /// (unix seconds, USDC) of buys in the last 24h.
+    #[serde(default)]
+    pub spends: Vec<(i64, f64)>,
+    /// UTC date the `realized_at_day_start` baseline belongs to.
+    #[serde(default)]
+    pub day: Option<String>,
+    #[serde(default)]
+    pub realized_at_day_start: f64,
+}
+
+/// `risk-state.json` in the same directory as the holdings file.
+pub fn risk_state_path(holdings_path: &Path) -> PathBuf {
+    holdings_path.with_file_name("risk-state.json")
+}
+
+fn load_state(path: &Path) -> RiskState {
+    if !path.exists() {
+        return RiskState::default();
+    }
+    match std::fs::read_to_string(path).map(|s| serde_json::from_str(&s)) {
+        Ok(Ok(state)) => state,
+        Ok(Err(e)) => {
+            error!("Failed to parse risk state: {}", e);
+            RiskState::default()
+        }
+        Err(e) => {
+            error!("Failed to load risk state: {}", e);
+            RiskState::default()
+        }
+    }
+}
+
+fn save_state(path: &Path, state: &RiskState) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+/// Exposure is the cost basis of open positions in the per-target ledgers plus the unfilled
+/// notional of resting BUYs, and realized PnL is the ledgers' total (sells and redemptions),
+/// so both survive restarts.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Exposure {
+    pub market: f64,
+    pub total: f64,
+    pub open_positions: usize,
+    /// Whether the token being bought is already held.
+    pub holds_token: bool,
+    pub realized_pnl: f64,
+}
+
+/// Gates copied BUYs on the limits in [`RiskConfig`]. Sells are never blocked: they only
+/// reduce exposure.
+#[derive(Debug, Clone)]
+pub struct RiskManager {
+    limits: RiskConfig,
+    ledger_path: PathBuf,
+    state_path: PathBuf,
+    resting_path: PathBuf,
+}
+
+impl RiskManager {
+    pub fn new(limits: RiskConfig, holdings_path: &Path) -> Self {
+        Self {
+            limits,
+            ledger_path: target_ledger_path(holdings_path),
+            state_path: risk_state_path(holdings_path),
+            resting_path: resting_orders_path(holdings_path),
+        }
+    }
+
+    pub fn exposure(&self, condition_id: &str, token_id: &str) -> Exposure {
+        let mut exposure = Exposure::default();
+        let ledgers = get_target_ledgers(&self.ledger_path);
+        let mut open = HashSet::new();
+        for ledger in ledgers.values() {
+            exposure.realized_pnl += ledger.realized_pnl;
+            for (market, tokens) in &ledger.positions {
+                for (token, position) in tokens {
+                    if position.shares <= 1e-9 {
+                        continue;
+                    }
+                    exposure.total += position.cost;
+                    if market == condition_id {
+                        exposure.market += position.cost;
+                        exposure.holds_token |= token == token_id;
+                    }
+                    open.insert((market.as_str(), token.as_str()));
+                }
+            }
+        }
+        let resting = self.resting_buys();
+        for order in &resting {
+            let notional = order.remaining() * order.price;
+            exposure.total += notional;
+            if order.condition_id == condition_id {
+                exposure.market += notional;
+                exposure.holds_token |= order.token_id == token_id;
+            }
+            open.insert((order.condition_id.as_str(), order.token_id.as_str()));
+        }
+        exposure.open_positions = open.len();
+        exposure
+    }
+
+    /// Tracked GTC/GTD BUYs with shares still unfilled.
+    fn resting_buys(&self) -> Vec<RestingOrder> {
+        load_resting_orders(&self.resting_path)
+            .into_iter()
+            .filter(|o| o.side == OrderSide::Buy && o.remaining() > 1e-9)
+            .collect()
+    }
+
+    pub fn check_buy(&self, condition_id: &str, token_id: &str, amount: f64) -> Result<Option<RiskViolation>> {
+        self.check_buy_at(condition_id, token_id, amount, Utc::now())
+    }
+
+    /// Starts the daily loss baseline if this is the first trade of the UTC day. Called before
+    /// a sell or redemption is booked so that its PnL counts towards today.
+    pub fn roll_day(&self) -> Result<()> {
+        self.state_at(self.exposure("", "").realized_pnl, Utc::now()).map(|_| ())
+    }
+
+    fn state_at(&self, realized_pnl: f64, now: DateTime<Utc>) -> Result<RiskState> {
+        let mut state = load_state(&self.state_path);
+        let today = now.date_naive().to_string();
+        if state.day.as_deref() != Some(today.as_str()) {
+            state.day = Some(today);
+            state.realized_at_day_start = realized_pnl;
+            save_state(&self.state_path, &state)?;
+        }
+        Ok(state)
+    }
+
+    /// The first limit `amount` USDC of `token_id` would break at `now`, if any. Realized PnL
+    /// counts from the first trade of the UTC day; the 24h spend includes what resting BUYs
+    /// may still spend.
+    pub fn check_buy_at(
+        &self,
+        condition_id: &str,
+        token_id: &str,
+        amount: f64,
+        now: DateTime<Utc>,
+    ) -> Result<Option<RiskViolation>> {
+        let exposure = self.exposure(condition_id, token_id);
+        let state = self.state_at(exposure.realized_pnl, now)?;
+
+        let l = &self.limits;
+        if let Some(limit) = l.max_daily_loss {
+            let realized = exposure.realized_pnl - state.realized_at_day_start;
+            if realized <= -limit {
+                return Ok(Some(RiskViolation::DailyLossLimit { realized, limit }));
+            }
+        }
+        if let Some(limit) = l.max_market_exposure {
+            if exposure.market + amount > limit {
+                return Ok(Some(RiskViolation::MarketExposure {
+                    market: condition_id.to_string(),
+                    current: exposure.market,
+                    order: amount,
+                    limit,
+                }));
+            }
+        }
+        if let Some(limit) = l.max_total_exposure {
+            if exposure.total + amount > limit {
+                return Ok(Some(RiskViolation::TotalExposure { current: exposure.total, order: amount, limit }));
+            }
+        }
+        if let Some(limit) = l.max_open_positions {
+            if !exposure.holds_token && exposure.open_positions >= limit {
+                return Ok(Some(RiskViolation::OpenPositions { open: exposure.open_positions, limit }));
+            }
+        }
+        if let Some(limit) = l.max_daily_spend {
+            let pending: f64 = self.resting_buys().iter().map(|o| o.remaining() * o.price).sum();
+            let spent = spent_since(&state, now.timestamp() - DAY_SECS) + pending;
+            if spent + amount > limit {
+                return Ok(Some(RiskViolation::DailySpend { spent, order: amount, limit }));
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn record_buy(&self, amount: f64) -> Result<()> {
+        self.record_buy_at(amount, Utc::now())
+    }
+
+    /// Adds a filled BUY to the rolling 24h spend.
+    pub fn record_buy_at(&self, amount: f64, now: DateTime<Utc>) -> Result<()> {
+        let mut state = load_state(&self.state_path);
+        let cutoff = now.timestamp() - DAY_SECS;
+        state.spends.retain(|(ts, _)| *ts > cutoff);
+        state.spends.push((now.timestamp(), amount));
+        save_state(&self.state_path, &state)
+    }
+
+    pub fn spent_last_24h(&self, now: DateTime<Utc>) -> f64 {
+        spent_since(&load_state(&self.state_path), now.timestamp() - DAY_SECS)
+    }
+}
+
+fn spent_since(state: &RiskState, cutoff: i64) -> f64 {
+    state.spends.iter().filter(|(ts, _)| *ts > cutoff).map(|(_, usdc)| usdc).sum()
+}
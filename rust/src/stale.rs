@@ -0,0 +1,80 @@
+use crate::clob::get_midpoint;
+use crate::config::StaleConfig;
+use crate::feed::{trade_latency, TradePayload};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+use tracing::info;
+
+/// Skips BUYs detected too long after the target made them, unless the market has not moved
+/// away from the target's price. SELLs are always copied (slippage limits still apply), and
+/// trades without a timestamp cannot be aged and pass. Skipped trades still belong in the
+/// target's position ([`crate::TradeOrderBuilder::record_target_trade`]).
+pub struct StaleTradeGuard {
+    config: StaleConfig,
+    clob_api_url: String,
+    skipped: AtomicU64,
+}
+
+impl StaleTradeGuard {
+    pub fn new(config: StaleConfig, clob_api_url: String) -> Self {
+        Self {
+            config,
+            clob_api_url,
+            skipped: AtomicU64::new(0),
+        }
+    }
+
+    /// Trades skipped as stale so far.
+    pub fn skipped(&self) -> u64 {
+        self.skipped.load(Ordering::SeqCst)
+    }
+
+    pub async fn check(&self, trade: &TradePayload) -> Result<(), String> {
+        self.check_at(trade, SystemTime::now()).await
+    }
+
+    /// `Err` says why `trade` is too stale to copy at `now`.
+    pub async fn check_at(&self, trade: &TradePayload, now: SystemTime) -> Result<(), String> {
+        let Some(max_age) = self.config.max_age_secs else {
+            return Ok(());
+        };
+        if trade.side.eq_ignore_ascii_case("SELL") {
+            return Ok(());
+        }
+        let Some(latency) = trade_latency(trade, now) else {
+            return Ok(());
+        };
+        if latency <= Duration::from_secs(max_age) {
+            return Ok(());
+        }
+        let Some(drift) = self.config.max_midpoint_drift else {
+            return Err(format!("detected {:?} after execution, over {}s", latency, max_age));
+        };
+        match get_midpoint(&self.clob_api_url, &trade.asset).await {
+            Ok(mid) if (mid - trade.price).abs() <= drift + 1e-9 => {
+                info!(
+                    "Copying BUY detected {:?} late: midpoint {} is within {} of the target's price {}",
+                    latency, mid, drift, trade.price
+                );
+                Ok(())
+            }
+            Ok(mid) => Err(format!(
+                "detected {:?} after execution and midpoint {} is more than {} from the target's price {}",
+                latency, mid, drift, trade.price
+            )),
+            Err(e) => Err(format!("detected {:?} after execution and no midpoint: {}", latency, e)),
+        }
+    }
+
+    /// [`Self::check`], logging and counting skipped trades.
+    pub async fn allows(&self, trade: &TradePayload, target_name: &str) -> bool {
+        match self.check(trade).await {
+            Ok(()) => true,
+            Err(reason) => {
+                self.skipped.fetch_add(1, Ordering::SeqCst);
+                info!("Skipping stale {} from {}: {}", trade.side, target_name, reason);
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use common::{serve, Reply};
+use polymarket_copy_bot::config::RiskConfig;
+use polymarket_copy_bot::holdings::{add_holdings, get_all_holdings};
+use polymarket_copy_bot::{auto_redeem_markets, RedeemConfig, RiskManager};
+use std::sync::{Arc, Mutex};
+
+const CONDITION_ID: &str = "0x5f65177b394277fd294cd75650044e32ba009a95022d88a0c1d565897d72f8f1";
+
+/// A Polygon RPC where the condition resolved to outcome 1 and the wallet holds no tokens of
+/// it; records the JSON-RPC method, and the selector of every `eth_call`.
+async fn resolved_rpc(calls: Arc<Mutex<Vec<String>>>) -> String {
+    serve(move |req| {
+        if !req.line.starts_with("POST") {
+            // CLOB market lookup: fall back to the configured neg-risk flag.
+            return Reply::status("404 Not Found", "{}");
+        }
+        let body: serde_json::Value = serde_json::from_str(req.body()).unwrap();
+        let method = body["method"].as_str().unwrap_or_default().to_string();
+        let data = body["params"][0]["data"].as_str().unwrap_or_default().to_string();
+        let selector = data.get(2..10).unwrap_or_default().to_string();
+        calls.lock().unwrap().push(if method == "eth_call" { selector.clone() } else { method.clone() });
+        let word = match selector.as_str() {
+            "d42dc0c2" => 2,                             // getOutcomeSlotCount
+            "dd34de67" => 1,                             // payoutDenominator
+            "0504c814" => u64::from(data.ends_with('1')), // payoutNumerators(cid, i)
+            "856296f7" | "39dd7530" => 7,                // getCollectionId, getPositionId
+            _ => 0,                                      // balanceOf
+        };
+        let reply = if method == "eth_call" {
+            serde_json::json!({ "jsonrpc": "2.0", "id": body["id"], "result": format!("0x{:064x}", word) })
+        } else {
+            serde_json::json!({ "jsonrpc": "2.0", "id": body["id"], "error": { "code": -32000, "message": "unexpected" } })
+        };
+        Reply::ok(reply.to_string())
+    })
+    .await
+}
+
+#[tokio::test]
+async fn resolved_market_without_winning_tokens_is_closed_without_a_transaction() {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let url = resolved_rpc(calls.clone()).await;
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    add_holdings(&holdings, CONDITION_ID, "losing-token", 10.0).unwrap();
+    let rc = RedeemConfig {
+        chain_id: 137,
+        private_key: format!("0x{}", "11".repeat(32)),
+        rpc_url: url.clone(),
+        clob_api_url: url,
+        neg_risk: false,
+    };
+    let risk = RiskManager::new(RiskConfig::default(), &holdings);
+
+    let summary =
+        auto_redeem_markets(&[CONDITION_ID.to_string()], &holdings, &rc, 1, &risk, &Mutex::new(())).await.unwrap();
+
+    assert_eq!((summary.resolved, summary.redeemed, summary.failed), (1, 0, 0));
+    assert!(!summary.results[0].redeemed);
+    let calls = calls.lock().unwrap();
+    assert_eq!(calls.iter().filter(|c| *c == "dd34de67").count(), 1, "resolution checked once: {:?}", calls);
+    assert!(!calls.iter().any(|c| c.starts_with("eth_")), "only eth_calls, nothing sent: {:?}", calls);
+    assert!(get_all_holdings(&holdings).is_empty());
+}
@@ -0,0 +1,99 @@
+use polymarket_copy_bot::backtest::{run_backtest_comparison, BacktestDataset, MarketResolution};
+use polymarket_copy_bot::{MarketFilter, TargetConfig, TradePayload};
+
+const TARGET: &str = "0x1111111111111111111111111111111111111111";
+const OTHER: &str = "0x2222222222222222222222222222222222222222";
+
+fn trade(wallet: &str, ts: u64, market: &str, token: &str, side: &str, price: f64, size: f64) -> TradePayload {
+    serde_json::from_value(serde_json::json!({
+        "proxyWallet": wallet,
+        "timestamp": ts,
+        "conditionId": market,
+        "asset": token,
+        "side": side,
+        "price": price,
+        "size": size,
+        "slug": format!("{}-slug", market),
+        "transactionHash": format!("0x{:x}", ts)
+    }))
+    .unwrap()
+}
+
+fn dataset() -> BacktestDataset {
+    let mut d = BacktestDataset {
+        trades: vec![
+            trade(TARGET, 300, "m1", "a", "SELL", 0.7, 20.0),
+            trade(TARGET, 100, "m1", "a", "BUY", 0.5, 20.0),
+            trade(OTHER, 150, "m1", "a", "BUY", 0.5, 1000.0),
+            trade(TARGET, 200, "m2", "b", "BUY", 0.4, 100.0),
+            trade(TARGET, 250, "m3", "c", "BUY", 0.5, 10.0),
+        ],
+        ..Default::default()
+    };
+    d.resolutions.insert("m2".to_string(), MarketResolution { closed: true, winners: vec!["b-no".to_string()] });
+    d
+}
+
+fn target() -> TargetConfig {
+    TargetConfig {
+        max_order_amount: Some(20.0),
+        markets: MarketFilter { exclude: vec!["m3".to_string()], ..Default::default() },
+        ..TargetConfig::new(TARGET)
+    }
+}
+
+#[tokio::test]
+async fn backtest_replays_settles_and_reports_clipping() {
+    let dir = tempfile::tempdir().unwrap();
+    let cmp = run_backtest_comparison(&dataset(), &target(), &MarketFilter::default(), 100.0, dir.path()).await.unwrap();
+    let r = &cmp.capped;
+
+    // Other wallets' trades are ignored; m3 is filtered out.
+    assert_eq!(r.trades_seen, 4);
+    assert_eq!(r.trades_copied, 3);
+    assert_eq!(r.trades_skipped, 1);
+
+    // a: 10 USDC -> 20 shares sold at 0.7 (+4). b: 40 USDC clipped to 20, loses at resolution (-20).
+    assert_eq!((r.wins, r.losses), (1, 1));
+    assert!((r.hit_rate() - 0.5).abs() < 1e-9);
+    assert!((r.final_equity - 84.0).abs() < 1e-9);
+    assert!((r.roi() + 0.16).abs() < 1e-9);
+    assert!((r.turnover - 44.0).abs() < 1e-9);
+    // Peak 104 after the sell (b marked at 0.4), then b settles at 0.
+    assert!((r.max_drawdown - 20.0 / 104.0).abs() < 1e-9);
+    assert_eq!(r.open_positions, 0);
+
+    assert_eq!(cmp.clipped_orders, 1);
+    assert!((cmp.uncapped.pnl() + 36.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn dataset_round_trips_through_the_cache() {
+    let dir = tempfile::tempdir().unwrap();
+    let original = dataset();
+    original.save(dir.path()).unwrap();
+    let loaded = BacktestDataset::load(dir.path()).unwrap();
+    assert_eq!(loaded.trades.len(), original.trades.len());
+    assert_eq!(loaded.trades[0].condition_id(), Some("m1"));
+    assert!(loaded.resolutions["m2"].is_resolved());
+
+    let a = run_backtest_comparison(&original, &target(), &MarketFilter::default(), 100.0, &dir.path().join("run")).await.unwrap();
+    let b = run_backtest_comparison(&loaded, &target(), &MarketFilter::default(), 100.0, &dir.path().join("run")).await.unwrap();
+    assert!((a.capped.final_equity - b.capped.final_equity).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn global_market_filter_applies_and_gamma_rules_are_reported_as_ignored() {
+    let dir = tempfile::tempdir().unwrap();
+    let markets = MarketFilter {
+        min_price: Some(0.45),
+        exclude_tags: vec!["sports".to_string()],
+        ..Default::default()
+    };
+    let cmp = run_backtest_comparison(&dataset(), &target(), &markets, 100.0, dir.path()).await.unwrap();
+    let r = &cmp.capped;
+
+    // m2's BUY at 0.4 is under the global minimum price, m3 is excluded by the target.
+    assert_eq!((r.trades_copied, r.trades_skipped), (2, 2));
+    assert!(r.metadata_rules_ignored);
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use common::{clob_client, order_builder, serve, Reply};
+use polymarket_copy_bot::balance::{get_available_balance, validate_buy_order_balance, Reserved};
+use polymarket_copy_bot::clob::OpenOrder;
+use polymarket_copy_bot::{add_holdings, get_holdings, record_target_buy, target_ledger_path, TargetConfig, TradePayload};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+const TARGET: &str = "0x1111111111111111111111111111111111111111";
+
+/// A resting BUY of 10 shares of token 1 at 0.5 with 4 filled, a SELL of 15 shares of
+/// token 1, and a cancelled BUY.
+const OPEN_ORDERS: &str = r#"{"data":[
+    {"id":"b","status":"LIVE","asset_id":"1","side":"BUY","original_size":"10","size_matched":"4","price":"0.5"},
+    {"id":"s","status":"LIVE","asset_id":"1","side":"SELL","original_size":"15","size_matched":"0","price":"0.7"},
+    {"id":"x","status":"CANCELED","asset_id":"2","side":"BUY","original_size":"100","size_matched":"0","price":"0.9"}
+],"next_cursor":"LTE="}"#;
+
+/// A CLOB stub with a 10 USDC balance and `OPEN_ORDERS`; returns the URL and the bodies of
+/// posted orders.
+async fn clob() -> (String, Arc<Mutex<Vec<String>>>) {
+    let posted = Arc::new(Mutex::new(Vec::new()));
+    let seen = posted.clone();
+    let url = serve(move |request| {
+        Reply::ok(if request.line.starts_with("GET /balance-allowance") {
+            r#"{"balance":"10000000","allowance":"1000000000"}"#
+        } else if request.line.starts_with("GET /data/orders") {
+            OPEN_ORDERS
+        } else if request.line == "POST /order" {
+            seen.lock().unwrap().push(request.body().to_string());
+            r#"{"success":true,"orderID":"o1","status":"matched","makingAmount":"5","takingAmount":"2.6"}"#
+        } else if request.line.starts_with("GET /book") {
+            r#"{"bids":[{"price":"0.52","size":"100"}],"asks":[]}"#
+        } else {
+            "null"
+        })
+    })
+    .await;
+    (url, posted)
+}
+
+#[test]
+fn reserved_collateral_is_remaining_shares_times_price() {
+    let value: serde_json::Value = serde_json::from_str(OPEN_ORDERS).unwrap();
+    let orders: Vec<OpenOrder> = serde_json::from_value(value["data"].clone()).unwrap();
+    let reserved = Reserved::from_orders(&orders);
+    assert_eq!(reserved.collateral, 3.0);
+    assert_eq!((reserved.shares_of("1"), reserved.shares_of("2")), (15.0, 0.0));
+}
+
+#[tokio::test]
+async fn available_balance_is_scaled_and_net_of_open_buys() {
+    let (url, _) = clob().await;
+    let (clob, _) = clob_client(url);
+    assert_eq!(get_available_balance(&clob, None).await.unwrap(), 7.0);
+}
+
+#[tokio::test]
+async fn buy_balance_check_fetches_the_balance_once() {
+    let fetches = Arc::new(AtomicUsize::new(0));
+    let counted = fetches.clone();
+    let url = serve(move |request| {
+        Reply::ok(if request.line.starts_with("GET /balance-allowance") {
+            counted.fetch_add(1, Ordering::SeqCst);
+            r#"{"balance":"10000000","allowance":"1000000000"}"#
+        } else {
+            OPEN_ORDERS
+        })
+    })
+    .await;
+    let (clob, _) = clob_client(url);
+
+    let check = validate_buy_order_balance(&clob, 8.0).await.unwrap();
+    assert!(!check.valid);
+    assert_eq!((check.balance, check.available), (10.0, 7.0));
+    assert_eq!(fetches.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn shares_in_open_sells_are_not_sold_again() {
+    let (url, posted) = clob().await;
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    add_holdings(&holdings, "m1", "1", 20.0).unwrap();
+    record_target_buy(&target_ledger_path(&holdings), TARGET, "m1", "1", 20.0, 10.0).unwrap();
+    let ob = order_builder(url, &holdings, "FAK");
+
+    let trade: TradePayload = serde_json::from_value(serde_json::json!({
+        "proxyWallet": TARGET,
+        "conditionId": "m1",
+        "asset": "1",
+        "side": "SELL",
+        "price": 0.52,
+        "size": 100.0
+    }))
+    .unwrap();
+    let result = ob.copy_trade(&trade, &TargetConfig::new(TARGET)).await.unwrap();
+
+    assert!(result.success, "{:?}", result.error);
+    // 15 of the 20 shares are already offered, so only 5 are sold.
+    let order: serde_json::Value = serde_json::from_str(&posted.lock().unwrap()[0]).unwrap();
+    assert_eq!(order["order"]["makerAmount"], "5000000");
+    assert_eq!(get_holdings(&holdings, "m1", "1"), 15.0);
+}
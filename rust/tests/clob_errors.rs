@@ -0,0 +1,44 @@
+mod common;
+
+use common::{clob_client, serve, Reply};
+use polymarket_copy_bot::clob::{ClobError, RetryPolicy};
+use polymarket_copy_bot::ClobClient;
+
+/// A CLOB stub answering every request with `status` and `body`.
+async fn clob(status: &'static str, body: &'static str) -> ClobClient {
+    let url = serve(move |_| Reply::status(status, body)).await;
+    clob_client(url).0.with_retry(RetryPolicy::none())
+}
+
+#[tokio::test]
+async fn zero_open_orders_is_not_a_failure() {
+    let clob = clob("200 OK", r#"{"data":[],"next_cursor":"LTE="}"#).await;
+    assert!(clob.get_open_orders(None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn failed_requests_are_typed() {
+    let cases = [
+        ("401 Unauthorized", r#"{"error":"Unauthorized/Invalid api key"}"#),
+        ("429 Too Many Requests", r#"{"error":"rate limited"}"#),
+        ("400 Bad Request", r#"{"error":"invalid asset_id"}"#),
+        ("503 Service Unavailable", "upstream down"),
+        ("200 OK", "<html>maintenance</html>"),
+    ];
+    let mut kinds = Vec::new();
+    for (status, body) in cases {
+        let err = clob(status, body).await.get_open_orders(None).await.unwrap_err();
+        let err = err.downcast_ref::<ClobError>().expect("a ClobError");
+        assert_eq!(err.body(), Some(body));
+        assert_eq!(err.status().map(|s| s.as_u16()), status[..3].parse().ok());
+        kinds.push(match err {
+            ClobError::Auth { .. } => "auth",
+            ClobError::RateLimited { .. } => "rate limited",
+            ClobError::Validation { .. } => "validation",
+            ClobError::Server { .. } => "server",
+            ClobError::Decode { .. } => "decode",
+            ClobError::Request { .. } => "request",
+        });
+    }
+    assert_eq!(kinds, ["auth", "rate limited", "validation", "server", "decode"]);
+}
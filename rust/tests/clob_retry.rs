@@ -0,0 +1,100 @@
+mod common;
+
+use common::{clob_client, serve, Reply};
+use polymarket_copy_bot::clob::retry::parse_retry_after;
+use polymarket_copy_bot::clob::{ClobError, RetryPolicy};
+use polymarket_copy_bot::ClobClient;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A CLOB stub replying to the n-th request with `replies[n]` (the last one repeats), each
+/// reply being `(status, extra header, body)`. Returns the URL and the request lines.
+async fn clob(replies: Vec<(&'static str, &'static str, &'static str)>) -> (String, Arc<Mutex<Vec<String>>>) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+    let url = serve(move |request| {
+        let index = {
+            let mut seen = seen.lock().unwrap();
+            seen.push(request.line.clone());
+            seen.len() - 1
+        };
+        let (status, header, body) = replies[index.min(replies.len() - 1)];
+        let reply = Reply::status(status, body);
+        if header.is_empty() {
+            reply
+        } else {
+            reply.header(header)
+        }
+    })
+    .await;
+    (url, requests)
+}
+
+fn client(url: String) -> ClobClient {
+    clob_client(url).0.with_retry(RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(10),
+        max_backoff: Duration::from_secs(1),
+    })
+}
+
+const ORDER: &str = "0xabc";
+const POSTED: &str = r#"{"success":true,"orderID":"0xabc","status":"live"}"#;
+
+#[tokio::test]
+async fn rate_limited_and_server_errors_are_retried() {
+    let (url, requests) = clob(vec![
+        ("429 Too Many Requests", "Retry-After: 0", "slow down"),
+        ("503 Service Unavailable", "", "busy"),
+        ("200 OK", "", "[]"),
+    ])
+    .await;
+    assert!(client(url).get_open_orders(None).await.unwrap().is_empty());
+    assert_eq!(requests.lock().unwrap().len(), 3);
+
+    // Rejections are not retried, nor are waits longer than the policy allows.
+    let (url, requests) = clob(vec![("400 Bad Request", "", "bad")]).await;
+    assert!(client(url).get_open_orders(None).await.is_err());
+    assert_eq!(requests.lock().unwrap().len(), 1);
+    let (url, requests) = clob(vec![("429 Too Many Requests", "Retry-After: 60", "later")]).await;
+    let err = client(url).get_open_orders(None).await.unwrap_err();
+    assert_eq!(err.downcast_ref::<ClobError>().unwrap().retry_after(), Some(Duration::from_secs(60)));
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn order_posts_are_retried_only_if_the_order_did_not_land() {
+    // The post failed but the order is there: no second post.
+    let (url, requests) = clob(vec![
+        ("502 Bad Gateway", "", "gateway"),
+        ("200 OK", "", r#"{"status":"LIVE","size_matched":"0"}"#),
+    ])
+    .await;
+    let resp = client(url).post_order(&serde_json::json!({}), ORDER).await.unwrap();
+    assert!(resp.success);
+    assert_eq!((resp.order_id.as_deref(), resp.status.as_deref()), (Some(ORDER), Some("live")));
+    assert_eq!(*requests.lock().unwrap(), ["POST /order", "GET /data/order/0xabc"]);
+
+    // Not there: posted again.
+    let (url, requests) = clob(vec![
+        ("502 Bad Gateway", "", "gateway"),
+        ("200 OK", "", "null"),
+        ("200 OK", "", POSTED),
+    ])
+    .await;
+    let resp = client(url).post_order(&serde_json::json!({}), ORDER).await.unwrap();
+    assert_eq!(resp.order_id.as_deref(), Some(ORDER));
+    assert_eq!(*requests.lock().unwrap(), ["POST /order", "GET /data/order/0xabc", "POST /order"]);
+
+    // Unknown whether it landed: give up rather than risk a duplicate.
+    let (url, requests) = clob(vec![("502 Bad Gateway", "", "gateway"), ("401 Unauthorized", "", "no")]).await;
+    assert!(client(url).post_order(&serde_json::json!({}), ORDER).await.is_err());
+    assert_eq!(requests.lock().unwrap().len(), 2);
+}
+
+#[test]
+fn retry_after_is_seconds_or_a_date() {
+    assert_eq!(parse_retry_after("3"), Some(Duration::from_secs(3)));
+    assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+    assert_eq!(parse_retry_after("soon"), None);
+}
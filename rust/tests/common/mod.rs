@@ -0,0 +1,125 @@
+//! Shared by the integration tests: a stub HTTP server, CLOB / order builder and order book fixtures.
+#![allow(dead_code)]
+
+use ethers::prelude::{Http, Provider};
+use ethers::signers::{LocalWallet, Signer};
+use polymarket_copy_bot::clob::{ApiCreds, BookLevel, OrderBook};
+use polymarket_copy_bot::{ClobClient, TradeOrderBuilder};
+use std::path::Path;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A request received by [`serve`].
+pub struct Request {
+    /// Method and path, e.g. `"GET /data/order/o1"`.
+    pub line: String,
+    /// The raw request, headers and body included.
+    pub text: String,
+}
+
+impl Request {
+    pub fn path(&self) -> &str {
+        self.line.split(' ').nth(1).unwrap_or_default()
+    }
+
+    pub fn body(&self) -> &str {
+        self.text.split("\r\n\r\n").nth(1).unwrap_or_default()
+    }
+}
+
+/// What [`serve`] answers a request with.
+pub struct Reply {
+    status: &'static str,
+    headers: String,
+    body: String,
+}
+
+impl Reply {
+    /// `200 OK` with a JSON `body`.
+    pub fn ok(body: impl Into<String>) -> Self {
+        Self::status("200 OK", body)
+    }
+
+    pub fn status(status: &'static str, body: impl Into<String>) -> Self {
+        Self { status, headers: String::new(), body: body.into() }
+    }
+
+    /// Adds a header line, e.g. `"Retry-After: 1"`.
+    pub fn header(mut self, header: &str) -> Self {
+        self.headers.push_str(header);
+        self.headers.push_str("\r\n");
+        self
+    }
+}
+
+/// Starts an HTTP server on 127.0.0.1 answering each request with `route(request)`, one
+/// request per connection; returns its base URL.
+pub async fn serve<F>(route: F) -> String
+where
+    F: Fn(&Request) -> Reply + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        while let Ok((mut sock, _)) = listener.accept().await {
+            let mut buf = vec![0u8; 8192];
+            let n = sock.read(&mut buf).await.unwrap();
+            let text = String::from_utf8_lossy(&buf[..n]).to_string();
+            // "GET /path HTTP/1.1" -> "GET /path"
+            let line = text.lines().next().and_then(|l| l.rsplit_once(' ')).map(|(l, _)| l.to_string());
+            let reply = route(&Request { line: line.unwrap_or_default(), text });
+            let response = format!(
+                "HTTP/1.1 {}\r\n{}Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                reply.status,
+                reply.headers,
+                reply.body.len(),
+                reply.body
+            );
+            sock.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+    url
+}
+
+/// Serves `body` as JSON to every request.
+pub async fn serve_json(body: serde_json::Value) -> String {
+    serve(move |_| Reply::ok(body.to_string())).await
+}
+
+/// A client for the CLOB at `url`, signing as a fresh random wallet.
+pub fn clob_client(url: String) -> (ClobClient, LocalWallet) {
+    let creds = ApiCreds { api_key: "key".to_string(), secret: String::new(), passphrase: String::new() };
+    let wallet = LocalWallet::new(&mut ethers::core::rand::thread_rng());
+    (ClobClient::new(url, creds, format!("{:?}", wallet.address()), 0), wallet)
+}
+
+/// A Polygon builder with `order_type` orders against the CLOB at `clob_url`, tick size 0.01,
+/// no neg-risk and an unreachable RPC.
+pub fn order_builder(clob_url: String, holdings_path: &Path, order_type: &str) -> TradeOrderBuilder {
+    let (clob, wallet) = clob_client(clob_url);
+    let provider = Provider::<Http>::try_from("http://127.0.0.1:1").unwrap();
+    TradeOrderBuilder::new(
+        clob,
+        provider,
+        wallet,
+        137,
+        holdings_path.to_path_buf(),
+        "0.01".to_string(),
+        false,
+        order_type.to_string(),
+    )
+}
+
+pub fn level(price: &str, size: &str) -> BookLevel {
+    BookLevel { price: price.to_string(), size: size.to_string() }
+}
+
+/// Bids 0.48/0.45/0.40 and asks 0.52/0.55/0.60, in the order the CLOB returns them (best
+/// price last).
+pub fn book() -> OrderBook {
+    OrderBook {
+        bids: vec![level("0.40", "100"), level("0.45", "20"), level("0.48", "10")],
+        asks: vec![level("0.60", "100"), level("0.55", "20"), level("0.52", "10")],
+        ..Default::default()
+    }
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use common::{serve, Reply};
+use polymarket_copy_bot::data_api::get_trade_activity;
+use polymarket_copy_bot::feed::{backfill_trades, BackfillConfig, SeenTrades};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const TARGET: &str = "0x1111111111111111111111111111111111111111";
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+fn trade(tx: &str, ts: u64) -> serde_json::Value {
+    serde_json::json!({
+        "proxyWallet": TARGET,
+        "timestamp": ts,
+        "conditionId": "0xabc",
+        "type": "TRADE",
+        "size": 10.0,
+        "usdcSize": 5.0,
+        "transactionHash": tx,
+        "price": 0.5,
+        "asset": "123",
+        "side": "BUY",
+        "outcome": "Yes",
+        "title": "Test market",
+        "slug": "test-market"
+    })
+}
+
+/// A Data API stub serving `body` as JSON; records the request lines.
+async fn activity_api(body: serde_json::Value) -> (String, Arc<Mutex<Vec<String>>>) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+    let url = serve(move |request| {
+        seen.lock().unwrap().push(request.line.clone());
+        Reply::ok(body.to_string())
+    })
+    .await;
+    (url, requests)
+}
+
+#[tokio::test]
+async fn backfill_queries_activity_since_last_trade() {
+    let since = now_secs() - 30;
+    let (url, requests) = activity_api(serde_json::json!([])).await;
+    let cfg = BackfillConfig { data_api_url: url, max_age: Duration::from_secs(120) };
+
+    let missed = backfill_trades(&cfg, TARGET, since, &mut SeenTrades::default()).await.unwrap();
+    assert!(missed.is_empty());
+
+    let request = requests.lock().unwrap()[0].clone();
+    assert!(request.starts_with("GET /activity?"), "{}", request);
+    assert!(request.contains(&format!("user={}", TARGET)));
+    assert!(request.contains("type=TRADE"));
+    assert!(request.contains(&format!("start={}", since)));
+}
+
+#[tokio::test]
+async fn backfill_dedupes_by_tx_hash_and_orders_oldest_first() {
+    let now = now_secs();
+    let (url, _) = activity_api(serde_json::json!([
+        trade("0xCCC", now - 5),
+        trade("0xaaa", now - 20),
+        trade("0xbbb", now - 10),
+        trade("0xbbb", now - 10),
+    ]))
+    .await;
+    let cfg = BackfillConfig { data_api_url: url, max_age: Duration::from_secs(120) };
+
+    // 0xaaa already arrived on the feed before the disconnect.
+    let mut seen = SeenTrades::default();
+    seen.insert("0xAAA");
+    let missed = backfill_trades(&cfg, TARGET, now - 60, &mut seen).await.unwrap();
+
+    let hashes: Vec<_> = missed.iter().map(|t| t.transaction_hash.as_deref().unwrap()).collect();
+    assert_eq!(hashes, ["0xbbb", "0xCCC"]);
+    assert!(seen.contains("0xccc"));
+}
+
+#[tokio::test]
+async fn backfill_drops_trades_older_than_max_age() {
+    let now = now_secs();
+    let (url, _) = activity_api(serde_json::json!([trade("0xold", now - 600), trade("0xnew", (now - 10) * 1000)])).await;
+    let cfg = BackfillConfig { data_api_url: url, max_age: Duration::from_secs(120) };
+
+    let mut seen = SeenTrades::default();
+    let missed = backfill_trades(&cfg, TARGET, now - 900, &mut seen).await.unwrap();
+
+    assert_eq!(missed.len(), 1);
+    assert_eq!(missed[0].transaction_hash.as_deref(), Some("0xnew"));
+    // Stale trades are still marked seen so a later backfill does not pick them up again.
+    assert!(seen.contains("0xold"));
+}
+
+#[tokio::test]
+async fn activity_is_paged_past_the_first_pages() {
+    // Five full pages of 500 and a short sixth: more than the old 2000-item cap.
+    let url = serve(|request| {
+        let offset: u64 = request
+            .path()
+            .split('&')
+            .find_map(|p| p.strip_prefix("offset="))
+            .and_then(|o| o.parse().ok())
+            .unwrap_or(0);
+        let n = if offset < 2500 { 500 } else { 7 };
+        let page: Vec<_> = (offset..offset + n).map(|i| trade(&format!("0x{:x}", i), 1_700_000_000 + i)).collect();
+        Reply::ok(serde_json::Value::from(page).to_string())
+    })
+    .await;
+
+    let trades = get_trade_activity(&url, TARGET, 0).await.unwrap();
+    assert_eq!(trades.len(), 2507);
+    assert_eq!(trades.last().unwrap().transaction_hash.as_deref(), Some("0x9ca"));
+}
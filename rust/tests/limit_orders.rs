@@ -0,0 +1,137 @@
+mod common;
+
+use common::{order_builder, serve, Reply};
+use polymarket_copy_bot::order::{
+    load_resting_orders, resting_orders_path, save_resting_orders, OrderSide, RestingOrder,
+};
+use polymarket_copy_bot::{
+    get_holdings, get_target_holdings, target_ledger_path, TargetConfig, TradeOrderBuilder, TradePayload,
+};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+const TARGET: &str = "0x1111111111111111111111111111111111111111";
+
+/// A CLOB stub: order `o1` rests with 4 shares filled until it is cancelled; `o2` was
+/// cancelled with 2 filled; posted orders get id `o3`. Returns the URL and request lines.
+async fn clob() -> (String, Arc<Mutex<Vec<String>>>) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+    let cancelled = AtomicBool::new(false);
+    let url = serve(move |request| {
+        seen.lock().unwrap().push(request.line.clone());
+        Reply::ok(match request.line.as_str() {
+            "GET /data/order/o1" if cancelled.load(Ordering::SeqCst) => r#"{"status":"CANCELED","size_matched":"4"}"#,
+            "GET /data/order/o1" => r#"{"status":"LIVE","size_matched":"4"}"#,
+            "GET /data/order/o2" => r#"{"status":"CANCELED","size_matched":"2"}"#,
+            "DELETE /order" | "DELETE /orders" => {
+                cancelled.store(request.text.contains("o1"), Ordering::SeqCst);
+                r#"{"canceled":["o1"],"not_canceled":{}}"#
+            }
+            "POST /order" => r#"{"success":true,"orderID":"o3","status":"live"}"#,
+            l if l.starts_with("GET /data/orders") => "[]",
+            _ => "null",
+        })
+    })
+    .await;
+    (url, requests)
+}
+
+fn builder(clob_url: String, holdings_path: &Path) -> TradeOrderBuilder {
+    order_builder(clob_url, holdings_path, "GTC").with_order_timeout(300)
+}
+
+fn resting(id: &str, token: &str, price: f64, placed_at: i64) -> RestingOrder {
+    RestingOrder {
+        order_id: id.to_string(),
+        target: TARGET.to_string(),
+        condition_id: "m1".to_string(),
+        token_id: token.to_string(),
+        side: OrderSide::Buy,
+        price,
+        size: 10.0,
+        size_matched: 0.0,
+        placed_at,
+    }
+}
+
+#[tokio::test]
+async fn only_confirmed_fills_reach_holdings() {
+    let (url, requests) = clob().await;
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let ledger = target_ledger_path(&holdings);
+    let now = chrono::Utc::now().timestamp();
+    save_resting_orders(
+        &resting_orders_path(&holdings),
+        &[resting("o1", "1", 0.5, now), resting("o2", "2", 0.4, now - 1000)],
+    )
+    .unwrap();
+    let ob = builder(url, &holdings);
+
+    ob.sync_resting_orders().await.unwrap();
+    ob.sync_resting_orders().await.unwrap();
+
+    // o1 is still resting with 4 of 10 shares; o2 timed out and was cancelled with 2.
+    assert_eq!(get_holdings(&holdings, "m1", "1"), 4.0);
+    assert_eq!(get_holdings(&holdings, "m1", "2"), 2.0);
+    assert_eq!(get_target_holdings(&ledger, TARGET, "m1", "1"), 4.0);
+    let left = ob.resting_orders();
+    assert_eq!(left.len(), 1);
+    assert_eq!((left[0].order_id.as_str(), left[0].size_matched, left[0].remaining()), ("o1", 4.0, 6.0));
+    assert_eq!(requests.lock().unwrap().iter().filter(|r| *r == "DELETE /order").count(), 1);
+}
+
+#[tokio::test]
+async fn target_sell_cancels_resting_buys_and_rests_a_sell() {
+    let (url, requests) = clob().await;
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let now = chrono::Utc::now().timestamp();
+    save_resting_orders(&resting_orders_path(&holdings), &[resting("o1", "1", 0.5, now)]).unwrap();
+    let ob = builder(url, &holdings);
+
+    let trade: TradePayload = serde_json::from_value(serde_json::json!({
+        "proxyWallet": TARGET,
+        "conditionId": "m1",
+        "asset": "1",
+        "side": "SELL",
+        "price": 0.6,
+        "size": 100.0
+    }))
+    .unwrap();
+    let result = ob.copy_trade(&trade, &TargetConfig::new(TARGET)).await.unwrap();
+
+    assert!(result.success, "{:?}", result.error);
+    assert_eq!(result.order_id.as_deref(), Some("o3"));
+    // The 4 shares the cancelled buy got are offered, and stay held until the sell fills.
+    assert_eq!(get_holdings(&holdings, "m1", "1"), 4.0);
+    let orders = load_resting_orders(&resting_orders_path(&holdings));
+    assert_eq!(orders.len(), 1);
+    assert_eq!((orders[0].side, orders[0].size, orders[0].price), (OrderSide::Sell, 4.0, 0.6));
+    let requests = requests.lock().unwrap();
+    let cancel = requests.iter().position(|r| r == "DELETE /orders").unwrap();
+    assert!(cancel < requests.iter().position(|r| r == "POST /order").unwrap());
+}
+
+#[tokio::test]
+async fn cancel_all_resting_keeps_the_last_fills() {
+    let (url, requests) = clob().await;
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let now = chrono::Utc::now().timestamp();
+    save_resting_orders(
+        &resting_orders_path(&holdings),
+        &[resting("o1", "1", 0.5, now), resting("o2", "2", 0.4, now)],
+    )
+    .unwrap();
+    let ob = builder(url, &holdings);
+
+    assert_eq!(ob.cancel_all_resting("Shutting down").await.unwrap(), 1);
+
+    assert!(ob.resting_orders().is_empty());
+    assert_eq!(get_holdings(&holdings, "m1", "1"), 4.0);
+    assert_eq!(get_holdings(&holdings, "m1", "2"), 2.0);
+    assert_eq!(requests.lock().unwrap().iter().filter(|r| *r == "DELETE /orders").count(), 1);
+}
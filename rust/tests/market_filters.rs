@@ -0,0 +1,139 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use common::serve_json;
+use polymarket_copy_bot::filter::CompiledFilter;
+use polymarket_copy_bot::gamma::GammaMarket;
+use polymarket_copy_bot::{Config, MarketFilter, TargetConfig, TradeFilter, TradePayload};
+use std::collections::HashMap;
+
+const KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const TARGET: &str = "0x1111111111111111111111111111111111111111";
+
+fn trade(side: &str, market: &str, slug: &str, price: f64) -> TradePayload {
+    serde_json::from_value(serde_json::json!({
+        "proxyWallet": TARGET,
+        "conditionId": market,
+        "asset": "1",
+        "side": side,
+        "price": price,
+        "size": 10.0,
+        "slug": slug,
+        "title": slug.replace('-', " ")
+    }))
+    .unwrap()
+}
+
+fn gamma(tags: &[&str], end_date: &str) -> GammaMarket {
+    serde_json::from_value(serde_json::json!({
+        "conditionId": "0xm1",
+        "endDate": end_date,
+        "tags": tags.iter().map(|t| serde_json::json!({ "label": t, "slug": t.to_lowercase() })).collect::<Vec<_>>()
+    }))
+    .unwrap()
+}
+
+#[test]
+fn static_rules_report_the_matching_rule() {
+    let filter = CompiledFilter::new(&MarketFilter {
+        exclude_regex: vec![r"^(nba|nfl)-".to_string()],
+        exclude_condition_ids: vec!["0xBAD".to_string()],
+        min_price: Some(0.03),
+        max_price: Some(0.97),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(filter.check(&trade("BUY", "0xm1", "btc-above-100k", 0.5), None), Ok(()));
+    assert_eq!(
+        filter.check(&trade("BUY", "0xm1", "NBA-lakers-celtics", 0.5), None),
+        Err(r#"exclude_regex: "^(nba|nfl)-""#.to_string())
+    );
+    assert_eq!(
+        filter.check(&trade("BUY", "0xbad", "btc-above-100k", 0.5), None),
+        Err("exclude_condition_ids: 0xBAD".to_string())
+    );
+    assert_eq!(
+        filter.check(&trade("BUY", "0xm1", "btc-above-100k", 0.98), None),
+        Err("max_price: 0.98 is above 0.97".to_string())
+    );
+
+    let allow_list = CompiledFilter::new(&MarketFilter { condition_ids: vec!["0xm1".to_string()], ..Default::default() }).unwrap();
+    assert!(allow_list.check(&trade("BUY", "0xm2", "x", 0.5), None).unwrap_err().starts_with("condition_ids"));
+}
+
+#[test]
+fn tag_and_end_date_rules_use_gamma_metadata() {
+    let filter = CompiledFilter::new(&MarketFilter {
+        exclude_tags: vec!["sports".to_string()],
+        min_hours_to_end: Some(6.0),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(filter.needs_metadata());
+    let now = Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap();
+    let t = trade("BUY", "0xm1", "lakers-celtics", 0.5);
+
+    assert_eq!(
+        filter.check_at(&t, Some(&gamma(&["Sports", "NBA"], "2026-03-02T00:00:00Z")), now),
+        Err(r#"exclude_tags: "sports""#.to_string())
+    );
+    assert_eq!(
+        filter.check_at(&t, Some(&gamma(&["Politics"], "2026-03-01T14:00:00Z")), now),
+        Err("min_hours_to_end: ends in 2.0h, under 6h".to_string())
+    );
+    assert_eq!(filter.check_at(&t, Some(&gamma(&["Politics"], "2026-03-02T00:00:00Z")), now), Ok(()));
+}
+
+#[tokio::test]
+async fn stage_checks_global_then_target_filters_and_lets_sells_through() {
+    let url = serve_json(serde_json::json!([{ "conditionId": "0xm1", "tags": [{ "label": "Sports", "slug": "sports" }] }])).await;
+    let target = TargetConfig {
+        label: Some("whale".to_string()),
+        markets: MarketFilter { exclude: vec!["preseason".to_string()], ..Default::default() },
+        ..TargetConfig::new(TARGET)
+    };
+    let global = MarketFilter { exclude_tags: vec!["sports".to_string()], ..Default::default() };
+    let stage = TradeFilter::new(&global, std::slice::from_ref(&target), Some(url)).unwrap();
+
+    assert_eq!(
+        stage.check(&trade("BUY", "0xm1", "lakers-celtics", 0.5), &target).await,
+        Err(r#"markets exclude_tags: "sports""#.to_string())
+    );
+    assert_eq!(stage.check(&trade("SELL", "0xm1", "lakers-celtics", 0.5), &target).await, Ok(()));
+
+    let offline = TradeFilter::new(&global, std::slice::from_ref(&target), None).unwrap();
+    assert_eq!(offline.check(&trade("BUY", "0xm1", "lakers-celtics", 0.5), &target).await, Ok(()));
+    assert_eq!(
+        offline.check(&trade("BUY", "0xm1", "nba-preseason", 0.5), &target).await,
+        Err(r#"whale exclude: "preseason""#.to_string())
+    );
+}
+
+#[tokio::test]
+async fn unknown_market_metadata_skips_the_trade() {
+    let url = serve_json(serde_json::json!([])).await;
+    let target = TargetConfig::new(TARGET);
+    let global = MarketFilter { min_hours_to_end: Some(1.0), ..Default::default() };
+    let stage = TradeFilter::new(&global, std::slice::from_ref(&target), Some(url)).unwrap();
+    assert_eq!(
+        stage.check(&trade("BUY", "0xm1", "x", 0.5), &target).await,
+        Err("market metadata unavailable".to_string())
+    );
+}
+
+#[test]
+fn invalid_filters_are_config_errors() {
+    let env: HashMap<&str, &str> = HashMap::from([("PRIVATE_KEY", KEY), ("FILTER_MAX_PRICE", "0.97")]);
+    let toml = format!(
+        r#"
+        [markets]
+        min_price = 0.99
+        [[targets]]
+        wallet = "{TARGET}"
+        markets = {{ exclude_regex = ["(unclosed"] }}
+        "#
+    );
+    let err = Config::resolve(&toml, |k| env.get(k).map(|v| v.to_string())).unwrap_err().to_string();
+    assert!(err.contains("markets: min_price 0.99 is above max_price 0.97"), "{}", err);
+    assert!(err.contains("targets[0].markets: invalid regex"), "{}", err);
+}
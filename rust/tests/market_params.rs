@@ -0,0 +1,54 @@
+mod common;
+
+use common::{clob_client, order_builder, serve, Reply};
+use polymarket_copy_bot::order::MarketParams;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A CLOB stub answering `/tick-size` and `/neg-risk` for token `1` only; counts requests.
+async fn clob() -> (String, Arc<AtomicUsize>) {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let count = requests.clone();
+    let url = serve(move |request| {
+        count.fetch_add(1, Ordering::SeqCst);
+        match request.line.as_str() {
+            "GET /tick-size?token_id=1" => Reply::ok(r#"{"minimum_tick_size":0.001}"#),
+            "GET /neg-risk?token_id=1" => Reply::ok(r#"{"neg_risk":true}"#),
+            _ => Reply::status("404 Not Found", r#"{"error":"market not found"}"#),
+        }
+    })
+    .await;
+    (url, requests)
+}
+
+#[tokio::test]
+async fn tick_size_and_neg_risk_are_cached() {
+    let (url, requests) = clob().await;
+    let (clob, _) = clob_client(url);
+
+    assert_eq!(clob.get_tick_size("1").await.unwrap(), "0.001");
+    assert!(clob.get_neg_risk("1").await.unwrap());
+    // Clones share the cache.
+    let copy = clob.clone();
+    assert_eq!(copy.get_tick_size("1").await.unwrap(), "0.001");
+    assert!(copy.get_neg_risk("1").await.unwrap());
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+    assert!(clob.get_tick_size("2").await.is_err());
+}
+
+#[tokio::test]
+async fn builder_falls_back_to_the_configured_values() {
+    let (url, _) = clob().await;
+    let dir = tempfile::tempdir().unwrap();
+    let ob = order_builder(url, &dir.path().join("token-holding.json"), "FAK");
+
+    assert_eq!(
+        ob.market_params("1").await,
+        MarketParams { tick_size: "0.001".to_string(), neg_risk: true }
+    );
+    assert_eq!(
+        ob.market_params("2").await,
+        MarketParams { tick_size: "0.01".to_string(), neg_risk: false }
+    );
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::{clob_client, serve, Reply};
+use std::sync::{Arc, Mutex};
+
+fn order(id: &str) -> String {
+    format!(
+        r#"{{"id":"{}","status":"LIVE","market":"m1","asset_id":"1","side":"BUY","original_size":"10","size_matched":"0","price":"0.5","order_type":"GTC","created_at":1700000000}}"#,
+        id
+    )
+}
+
+/// A CLOB stub serving open orders in pages of two; records the request paths.
+async fn clob() -> (String, Arc<Mutex<Vec<String>>>) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = requests.clone();
+    let url = serve(move |request| {
+        let path = request.path();
+        seen.lock().unwrap().push(path.to_string());
+        Reply::ok(match path.rsplit_once("next_cursor=").map(|(_, c)| c) {
+            Some("MA==") => format!(r#"{{"data":[{},{}],"next_cursor":"Mg=="}}"#, order("a"), order("b")),
+            Some("Mg==") => format!(r#"{{"data":[{}],"next_cursor":"LTE="}}"#, order("c")),
+            _ => "[]".to_string(),
+        })
+    })
+    .await;
+    (url, requests)
+}
+
+#[tokio::test]
+async fn open_orders_follow_the_cursor_to_the_last_page() {
+    let (url, requests) = clob().await;
+    let (clob, _) = clob_client(url);
+
+    let orders = clob.get_open_orders(Some("1")).await.unwrap();
+    let ids: Vec<_> = orders.iter().filter_map(|o| o.id.as_deref()).collect();
+    assert_eq!(ids, ["a", "b", "c"]);
+    assert_eq!(
+        *requests.lock().unwrap(),
+        ["/data/orders?asset_id=1&next_cursor=MA==", "/data/orders?asset_id=1&next_cursor=Mg=="]
+    );
+
+    let o = &orders[0];
+    assert_eq!(
+        (o.market.as_deref(), o.asset_id.as_deref(), o.price.as_deref(), o.order_type.as_deref()),
+        (Some("m1"), Some("1"), Some("0.5"), Some("GTC"))
+    );
+    assert_eq!((o.created_at, o.is_live()), (Some(1700000000), true));
+}
@@ -0,0 +1,173 @@
+mod common;
+
+use common::{order_builder, serve, Reply};
+use polymarket_copy_bot::clob::ClobTrade;
+use polymarket_copy_bot::order::{fill_from_response, fill_from_trades, Fill, OrderSide};
+use polymarket_copy_bot::{
+    add_holdings, get_holdings, get_target_ledgers, record_target_buy, target_ledger_path, TargetConfig,
+    TradeOrderBuilder, TradePayload,
+};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+const TARGET: &str = "0x1111111111111111111111111111111111111111";
+
+/// A CLOB stub that answers `POST /order` with `posted`. Order `o2` matched 20 shares at
+/// 0.52 (found in our trades); order `o3` was cancelled unfilled.
+async fn clob(posted: &'static str) -> String {
+    serve(move |request| {
+        Reply::ok(match request.line.as_str() {
+            "POST /order" => posted,
+            "GET /data/order/o2" => r#"{"status":"MATCHED","size_matched":"20"}"#,
+            "GET /data/order/o3" => r#"{"status":"CANCELED","size_matched":"0"}"#,
+            l if l.starts_with("GET /book") => r#"{"bids":[{"price":"0.52","size":"100"}],"asks":[]}"#,
+            l if l.starts_with("GET /data/orders") => "[]",
+            l if l.starts_with("GET /data/trades") => {
+                r#"{"data":[{"taker_order_id":"o2","size":"20","price":"0.52"}],"next_cursor":"LTE="}"#
+            }
+            _ => "null",
+        })
+    })
+    .await
+}
+
+/// A FAK builder holding 20 shares of token 1 bought from the target for 10 USDC.
+fn builder(clob_url: String, holdings_path: &Path) -> TradeOrderBuilder {
+    add_holdings(holdings_path, "m1", "1", 20.0).unwrap();
+    record_target_buy(&target_ledger_path(holdings_path), TARGET, "m1", "1", 20.0, 10.0).unwrap();
+    order_builder(clob_url, holdings_path, "FAK")
+}
+
+fn target_sell() -> TradePayload {
+    serde_json::from_value(serde_json::json!({
+        "proxyWallet": TARGET,
+        "conditionId": "m1",
+        "asset": "1",
+        "side": "SELL",
+        "price": 0.52,
+        "size": 100.0
+    }))
+    .unwrap()
+}
+
+fn realized(holdings_path: &Path) -> f64 {
+    get_target_ledgers(&target_ledger_path(holdings_path))[TARGET].realized_pnl
+}
+
+#[test]
+fn fills_come_from_response_amounts_or_trades() {
+    assert_eq!(
+        fill_from_response(OrderSide::Buy, Some(6.0), Some(12.0)),
+        Some(Fill { shares: 12.0, usdc: 6.0 })
+    );
+    assert_eq!(
+        fill_from_response(OrderSide::Sell, Some(12.0), Some(6.0)),
+        Some(Fill { shares: 12.0, usdc: 6.0 })
+    );
+    assert_eq!(fill_from_response(OrderSide::Buy, Some(0.0), Some(0.0)), None);
+
+    let trades: Vec<ClobTrade> = serde_json::from_value(serde_json::json!([
+        { "taker_order_id": "a", "size": "10", "price": "0.5" },
+        { "taker_order_id": "x", "size": "5", "price": "0.6",
+          "maker_orders": [{ "order_id": "a", "matched_amount": "4", "price": "0.55" }] },
+        { "taker_order_id": "y", "size": "3", "price": "0.6" }
+    ]))
+    .unwrap();
+    let fill = fill_from_trades("a", &trades);
+    assert_eq!(fill.shares, 14.0);
+    assert!((fill.usdc - 7.2).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn holdings_follow_the_reported_partial_fill() {
+    let url = clob(r#"{"success":true,"orderID":"o1","status":"matched","makingAmount":"12","takingAmount":"6.24"}"#).await;
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let ob = builder(url, &holdings);
+
+    let result = ob.copy_trade(&target_sell(), &TargetConfig::new(TARGET)).await.unwrap();
+    assert!(result.success, "{:?}", result.error);
+    assert_eq!(get_holdings(&holdings, "m1", "1"), 8.0);
+    // 12 of the 20 shares (cost 6.00) sold for 6.24
+    assert!((realized(&holdings) - 0.24).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn delayed_orders_are_polled_for_their_fill() {
+    let url = clob(r#"{"success":true,"orderID":"o2","status":"delayed"}"#).await;
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let ob = builder(url, &holdings);
+
+    let result = ob.copy_trade(&target_sell(), &TargetConfig::new(TARGET)).await.unwrap();
+    assert!(result.success, "{:?}", result.error);
+    assert_eq!((result.making_amount, result.taking_amount), (Some(20.0), Some(10.4)));
+    assert_eq!(get_holdings(&holdings, "m1", "1"), 0.0);
+    assert!((realized(&holdings) - 0.4).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn orders_that_fill_nothing_leave_holdings_alone() {
+    let url = clob(r#"{"success":true,"orderID":"o3","status":"delayed"}"#).await;
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let ob = builder(url, &holdings);
+
+    let result = ob.copy_trade(&target_sell(), &TargetConfig::new(TARGET)).await.unwrap();
+    assert!(!result.success);
+    assert_eq!(result.error.as_deref(), Some("SELL order o3 filled nothing; holdings unchanged"));
+    assert_eq!(get_holdings(&holdings, "m1", "1"), 20.0);
+    assert_eq!(realized(&holdings), 0.0);
+}
+
+#[tokio::test]
+async fn holdings_from_before_the_target_ledger_can_be_sold() {
+    let url = clob(r#"{"success":true,"orderID":"o1","status":"matched","makingAmount":"20","takingAmount":"10.4"}"#).await;
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let ob = builder(url, &holdings);
+    // An upgraded install: holdings but no per-target ledger yet.
+    std::fs::remove_file(target_ledger_path(&holdings)).unwrap();
+
+    let result = ob.copy_trade(&target_sell(), &TargetConfig::new(TARGET)).await.unwrap();
+    assert!(result.success, "{:?}", result.error);
+    assert_eq!(get_holdings(&holdings, "m1", "1"), 0.0);
+    // Adopted at zero cost, so the whole proceeds are realized.
+    assert!((realized(&holdings) - 10.4).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn orders_with_an_unknown_fill_are_tracked_until_the_clob_reports_it() {
+    let known = Arc::new(AtomicBool::new(false));
+    let reported = known.clone();
+    let url = serve(move |request| {
+        Reply::ok(match request.line.as_str() {
+            "POST /order" => r#"{"success":true,"orderID":"o4","status":"delayed"}"#,
+            "GET /data/order/o4" if reported.load(Ordering::SeqCst) => r#"{"status":"MATCHED","size_matched":"20"}"#,
+            l if l.starts_with("GET /data/trades") => {
+                r#"{"data":[{"taker_order_id":"o4","size":"20","price":"0.55"}],"next_cursor":"LTE="}"#
+            }
+            l if l.starts_with("GET /book") => r#"{"bids":[{"price":"0.52","size":"100"}],"asks":[]}"#,
+            l if l.starts_with("GET /data/orders") => "[]",
+            _ => "null",
+        })
+    })
+    .await;
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let ob = builder(url, &holdings);
+
+    let result = ob.copy_trade(&target_sell(), &TargetConfig::new(TARGET)).await.unwrap();
+    assert!(result.success);
+    assert_eq!(result.error.as_deref(), Some("SELL order o4 fill unknown; tracked until the CLOB reports it"));
+    assert_eq!(get_holdings(&holdings, "m1", "1"), 20.0);
+    assert_eq!(ob.resting_orders().len(), 1);
+
+    known.store(true, Ordering::SeqCst);
+    ob.sync_resting_orders().await.unwrap();
+    assert!(ob.resting_orders().is_empty());
+    assert_eq!(get_holdings(&holdings, "m1", "1"), 0.0);
+    // Booked at the 0.55 it traded at, not the target's 0.52.
+    assert!((realized(&holdings) - 1.0).abs() < 1e-9);
+}
@@ -0,0 +1,163 @@
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::transaction::eip712::{Eip712, TypedData};
+use ethers::types::{Address, U256};
+use polymarket_copy_bot::order::{limit_order_amounts, market_order_amounts, order_hash, sign_order, Order, OrderSide};
+
+// Well-known test key (hardhat account #0); never holds funds.
+const TEST_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const TOKEN_ID: &str = "71321045679252212594626385532706912750332728571942532289631379312455583992563";
+
+fn test_wallet() -> LocalWallet {
+    TEST_KEY.parse().unwrap()
+}
+
+fn test_order(side: OrderSide, amount: f64, price: f64) -> Order {
+    let addr = test_wallet().address();
+    Order::market(479249096354, addr, addr, TOKEN_ID, side, amount, price, "0.01", 0, 0).unwrap()
+}
+
+fn typed_data(order: &Order, verifying_contract: &str) -> TypedData {
+    serde_json::from_value(serde_json::json!({
+        "primaryType": "Order",
+        "domain": {
+            "name": "Polymarket CTF Exchange",
+            "version": "1",
+            "chainId": 137,
+            "verifyingContract": verifying_contract
+        },
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" }
+            ],
+            "Order": [
+                { "name": "salt", "type": "uint256" },
+                { "name": "maker", "type": "address" },
+                { "name": "signer", "type": "address" },
+                { "name": "taker", "type": "address" },
+                { "name": "tokenId", "type": "uint256" },
+                { "name": "makerAmount", "type": "uint256" },
+                { "name": "takerAmount", "type": "uint256" },
+                { "name": "expiration", "type": "uint256" },
+                { "name": "nonce", "type": "uint256" },
+                { "name": "feeRateBps", "type": "uint256" },
+                { "name": "side", "type": "uint8" },
+                { "name": "signatureType", "type": "uint8" }
+            ]
+        },
+        "message": {
+            "salt": order.salt.to_string(),
+            "maker": format!("{:?}", order.maker),
+            "signer": format!("{:?}", order.signer),
+            "taker": format!("{:?}", order.taker),
+            "tokenId": order.token_id.to_string(),
+            "makerAmount": order.maker_amount.to_string(),
+            "takerAmount": order.taker_amount.to_string(),
+            "expiration": order.expiration.to_string(),
+            "nonce": order.nonce.to_string(),
+            "feeRateBps": order.fee_rate_bps.to_string(),
+            "side": if order.side == OrderSide::Buy { 0 } else { 1 },
+            "signatureType": order.signature_type
+        }
+    }))
+    .unwrap()
+}
+
+#[test]
+fn market_buy_amounts_match_clob_client_rounding() {
+    // $10 at 0.55 -> 18.1818 shares (taker rounded to 4 dp for tick 0.01)
+    let (maker, taker) = market_order_amounts(OrderSide::Buy, 10.0, 0.55, "0.01").unwrap();
+    assert_eq!(maker, U256::from(10_000_000u64));
+    assert_eq!(taker, U256::from(18_181_800u64));
+}
+
+#[test]
+fn market_sell_amounts_match_clob_client_rounding() {
+    // 12.345 shares is floored to 12.34, sold at 0.37 -> 4.5658 USDC
+    let (maker, taker) = market_order_amounts(OrderSide::Sell, 12.345, 0.37, "0.01").unwrap();
+    assert_eq!(maker, U256::from(12_340_000u64));
+    assert_eq!(taker, U256::from(4_565_800u64));
+}
+
+#[test]
+fn limit_amounts_match_clob_client_rounding() {
+    // 18.189 shares is floored to 18.18; at 0.55 that costs 9.999 USDC
+    let (maker, taker) = limit_order_amounts(OrderSide::Buy, 18.189, 0.55, "0.01").unwrap();
+    assert_eq!(maker, U256::from(9_999_000u64));
+    assert_eq!(taker, U256::from(18_180_000u64));
+    let (maker, taker) = limit_order_amounts(OrderSide::Sell, 12.345, 0.37, "0.01").unwrap();
+    assert_eq!(maker, U256::from(12_340_000u64));
+    assert_eq!(taker, U256::from(4_565_800u64));
+}
+
+#[test]
+fn order_hash_matches_generic_eip712_encoder() {
+    for (neg_risk, exchange) in [
+        (false, "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E"),
+        (true, "0xC5d563A36AE78145C45a50134d48A1215220f80a"),
+    ] {
+        let order = test_order(OrderSide::Buy, 10.0, 0.55);
+        let ours = order_hash(&order, 137, neg_risk).unwrap();
+        let generic = typed_data(&order, exchange).encode_eip712().unwrap();
+        assert_eq!(ours.as_bytes(), generic.as_slice());
+    }
+}
+
+#[test]
+fn golden_buy_order_hash_and_signature() {
+    let order = test_order(OrderSide::Buy, 10.0, 0.55);
+    let hash = order_hash(&order, 137, false).unwrap();
+    assert_eq!(format!("{:?}", hash), "0x145871732223cf01f46a3c0ea654cf513b7a009f9ed275acd30ef0421f5b4d0d");
+
+    let signed = sign_order(&test_wallet(), order, 137, false).unwrap();
+    assert_eq!(signed.signature, "0x9f8562f14e4a0bb1f57bccdf3a920fc3abddc6c64a682b38d6cc55d77f151926529d2295adc2cf81cc1efcaa1208513ef83a9606b1baa9da32dce499562f35871b");
+}
+
+#[test]
+fn golden_neg_risk_sell_order_hash_and_signature() {
+    let order = test_order(OrderSide::Sell, 12.345, 0.37);
+    let hash = order_hash(&order, 137, true).unwrap();
+    assert_eq!(format!("{:?}", hash), "0x855a1bd8430bbc9b3eb8cce707c67e63a4ea9289c0b7e8d9f3d774dbe501e2e8");
+
+    let signed = sign_order(&test_wallet(), order, 137, true).unwrap();
+    assert_eq!(signed.signature, "0xe7ebeb77901f958329ff228271f4262e265ff7a81a2150bc2d528f6b1ee4a359526400d857d64f152106121fc05ffe3496ecb3f58e9da340c5aa978433d44ec51b");
+    let recovered = signed.signature.parse::<ethers::types::Signature>().unwrap();
+    let hash = order_hash(&signed.order, 137, true).unwrap();
+    assert_eq!(recovered.recover(hash).unwrap(), test_wallet().address());
+}
+
+#[test]
+fn post_body_matches_clob_client_order_json() {
+    let signed = sign_order(&test_wallet(), test_order(OrderSide::Buy, 10.0, 0.55), 137, false).unwrap();
+    let body = signed.to_post_body("api-key", "FAK");
+    let order = &body["order"];
+    assert_eq!(order["salt"], 479249096354u64);
+    assert_eq!(order["maker"], "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+    assert_eq!(order["taker"], format!("{:?}", Address::zero()));
+    assert_eq!(order["tokenId"], TOKEN_ID);
+    assert_eq!(order["makerAmount"], "10000000");
+    assert_eq!(order["takerAmount"], "18181800");
+    assert_eq!(order["side"], "BUY");
+    assert_eq!(order["signatureType"], 0);
+    assert_eq!(body["owner"], "api-key");
+    assert_eq!(body["orderType"], "FAK");
+}
+
+#[test]
+fn proxy_orders_are_made_by_the_funder_and_signed_by_the_key() {
+    let funder: Address = "0x2222222222222222222222222222222222222222".parse().unwrap();
+    let signer = test_wallet().address();
+    let order = Order::market(479249096354, funder, signer, TOKEN_ID, OrderSide::Buy, 10.0, 0.55, "0.01", 0, 2).unwrap();
+    let generic = typed_data(&order, "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E").encode_eip712().unwrap();
+    assert_eq!(order_hash(&order, 137, false).unwrap().as_bytes(), generic.as_slice());
+
+    let signed = sign_order(&test_wallet(), order, 137, false).unwrap();
+    let recovered = signed.signature.parse::<ethers::types::Signature>().unwrap();
+    assert_eq!(recovered.recover(order_hash(&signed.order, 137, false).unwrap()).unwrap(), signer);
+    let body = signed.to_post_body("api-key", "FAK");
+    assert_eq!(body["order"]["maker"], "0x2222222222222222222222222222222222222222");
+    assert_eq!(body["order"]["signer"], "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+    assert_eq!(body["order"]["signatureType"], 2);
+}
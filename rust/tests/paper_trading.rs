@@ -0,0 +1,56 @@
+mod common;
+
+use common::book;
+use polymarket_copy_bot::order::{simulate_market_fill, OrderSide, PaperAccount};
+
+#[test]
+fn buy_walks_asks_from_the_best_price() {
+    // 10 @ 0.52 = 5.2, then 10 USDC left buys 18.18 @ 0.55
+    let fill = simulate_market_fill(&book(), OrderSide::Buy, 15.2, 0.55, "FAK");
+    assert!(fill.complete);
+    assert!((fill.usdc - 15.2).abs() < 1e-9);
+    assert!((fill.shares - (10.0 + 10.0 / 0.55)).abs() < 1e-9);
+}
+
+#[test]
+fn buy_stops_at_the_price_limit() {
+    // Only 10 @ 0.52 and 20 @ 0.55 are within the limit: 16.2 USDC of 50
+    let fak = simulate_market_fill(&book(), OrderSide::Buy, 50.0, 0.55, "FAK");
+    assert!(!fak.complete);
+    assert!((fak.usdc - 16.2).abs() < 1e-9);
+    assert_eq!(fak.shares, 30.0);
+
+    let fok = simulate_market_fill(&book(), OrderSide::Buy, 50.0, 0.55, "FOK");
+    assert_eq!(fok.shares, 0.0);
+    assert_eq!(fok.usdc, 0.0);
+}
+
+#[test]
+fn sell_walks_bids_down_to_the_limit() {
+    let fill = simulate_market_fill(&book(), OrderSide::Sell, 25.0, 0.45, "FAK");
+    assert!(fill.complete);
+    assert_eq!(fill.shares, 25.0);
+    assert!((fill.usdc - (10.0 * 0.48 + 15.0 * 0.45)).abs() < 1e-9);
+    assert!((fill.avg_price().unwrap() - fill.usdc / 25.0).abs() < 1e-12);
+}
+
+#[test]
+fn account_tracks_balance_and_realized_pnl() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("paper-account.json");
+
+    let mut account = PaperAccount::load(&path, 100.0);
+    let buy = simulate_market_fill(&book(), OrderSide::Buy, 5.2, 0.52, "FAK");
+    assert_eq!(account.apply_fill("tok", OrderSide::Buy, &buy), 0.0);
+    account.save(&path).unwrap();
+
+    let mut account = PaperAccount::load(&path, 100.0);
+    assert!((account.usdc_balance - 94.8).abs() < 1e-9);
+    let sell = simulate_market_fill(&book(), OrderSide::Sell, 5.0, 0.4, "FAK");
+    let pnl = account.apply_fill("tok", OrderSide::Sell, &sell);
+    // Sold half the position (cost 2.6) for 5 @ 0.48 = 2.4
+    assert!((pnl + 0.2).abs() < 1e-9);
+    assert!((account.usdc_balance - 97.2).abs() < 1e-9);
+    assert_eq!(account.positions["tok"].shares, 5.0);
+    assert_eq!(account.fills, 2);
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use common::serve_json;
+use polymarket_copy_bot::backtest::{run_backtest, BacktestDataset};
+use polymarket_copy_bot::order::{paper_account_path, sell_amount, PaperAccount, TargetPositions, MIN_ORDER_SHARES};
+use polymarket_copy_bot::{MarketFilter, SellMode, TargetConfig, TradePayload};
+
+const TARGET: &str = "0x1111111111111111111111111111111111111111";
+
+fn trade(ts: u64, side: &str, price: f64, size: f64) -> TradePayload {
+    serde_json::from_value(serde_json::json!({
+        "proxyWallet": TARGET,
+        "timestamp": ts,
+        "conditionId": "0xabc",
+        "asset": "123",
+        "side": side,
+        "price": price,
+        "size": size,
+        "transactionHash": format!("0x{:x}", ts)
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn unseen_position_is_seeded_from_data_api() {
+    // The target already held shares; after selling 25 the Data API reports 100 left.
+    let url = serve_json(serde_json::json!([
+        { "asset": "123", "conditionId": "0xabc", "size": 100.0 },
+        { "asset": "456", "conditionId": "0xabc", "size": 7.0 }
+    ]))
+    .await;
+    let positions = TargetPositions::new(Some(url));
+
+    let fraction = positions.record_sell(TARGET, &trade(1, "SELL", 0.5, 25.0)).await;
+    assert!((fraction - 0.2).abs() < 1e-9);
+    assert_eq!(positions.size(TARGET, "123"), Some(100.0));
+
+    // Known from here on: no further request is made.
+    let fraction = positions.record_sell(&TARGET.to_uppercase(), &trade(2, "SELL", 0.5, 50.0)).await;
+    assert!((fraction - 0.5).abs() < 1e-9);
+    positions.record_buy(TARGET, &trade(3, "BUY", 0.5, 10.0)).await;
+    assert_eq!(positions.size(TARGET, "123"), Some(60.0));
+}
+
+#[tokio::test]
+async fn unknown_position_without_data_api_is_a_full_exit() {
+    let positions = TargetPositions::default();
+    assert_eq!(positions.record_sell(TARGET, &trade(1, "SELL", 0.5, 25.0)).await, 1.0);
+    assert_eq!(positions.size(TARGET, "123"), None);
+}
+
+#[test]
+fn sell_amount_respects_mode_and_minimum_remaining() {
+    let mut target = TargetConfig::new(TARGET);
+    assert_eq!(target.sell_mode, SellMode::Proportional);
+    assert!((sell_amount(100.0, 0.25, &target) - 25.0).abs() < 1e-9);
+    // 97 would leave 3 shares, below the default minimum of 5.
+    assert_eq!(sell_amount(100.0, 0.97, &target), 100.0);
+    assert_eq!(sell_amount(100.0, 0.0, &target), 0.0);
+
+    target.min_remaining_shares = 0.0;
+    assert!((sell_amount(100.0, 0.97, &target) - 97.0).abs() < 1e-9);
+
+    target.sell_mode = SellMode::Full;
+    assert_eq!(sell_amount(100.0, 0.25, &target), 100.0);
+}
+
+#[test]
+fn partial_sells_below_the_minimum_order_size_are_skipped() {
+    let target = TargetConfig::new(TARGET);
+    // 0.8 shares cannot be posted; the position is kept.
+    assert_eq!(sell_amount(20.0, 0.04, &target), 0.0);
+    assert!((sell_amount(20.0, 0.25, &target) - MIN_ORDER_SHARES).abs() < 1e-9);
+    // A whole position is sold even when it is small.
+    assert_eq!(sell_amount(3.0, 0.5, &target), 3.0);
+}
+
+#[tokio::test]
+async fn copied_sell_matches_the_targets_fraction() {
+    let dir = tempfile::tempdir().unwrap();
+    let dataset = BacktestDataset {
+        trades: vec![trade(1, "BUY", 0.5, 100.0), trade(2, "SELL", 0.6, 25.0)],
+        ..Default::default()
+    };
+    let report = run_backtest(&dataset, &TargetConfig::new(TARGET), &MarketFilter::default(), 1000.0, dir.path()).await.unwrap();
+    assert_eq!(report.trades_copied, 2);
+
+    let account = PaperAccount::load(&paper_account_path(&dir.path().join("token-holding.json")), 1000.0);
+    assert!((account.positions["123"].shares - 75.0).abs() < 1e-9);
+    assert!((account.realized_pnl - 2.5).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn filtered_buys_still_count_towards_the_targets_position() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut target = TargetConfig::new(TARGET);
+    target.markets.max_price = Some(0.9);
+    // The second BUY is filtered out, but the target now holds 200: its SELL of 100 is half.
+    let dataset = BacktestDataset {
+        trades: vec![trade(1, "BUY", 0.5, 100.0), trade(2, "BUY", 0.95, 100.0), trade(3, "SELL", 0.6, 100.0)],
+        ..Default::default()
+    };
+    let report = run_backtest(&dataset, &target, &MarketFilter::default(), 1000.0, dir.path()).await.unwrap();
+    assert_eq!((report.trades_copied, report.trades_skipped), (2, 1));
+
+    let account = PaperAccount::load(&paper_account_path(&dir.path().join("token-holding.json")), 1000.0);
+    assert!((account.positions["123"].shares - 50.0).abs() < 1e-9);
+}
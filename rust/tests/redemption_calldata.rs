@@ -0,0 +1,63 @@
+use ethers::types::U256;
+use polymarket_copy_bot::chain::get_contract_config;
+use polymarket_copy_bot::redemption::{condition_id_bytes32, redeem_call, RedeemRoute};
+
+const CONDITION_ID: &str = "0x5f65177b394277fd294cd75650044e32ba009a95022d88a0c1d565897d72f8f1";
+
+#[test]
+fn ctf_route_encodes_redeem_positions_with_index_sets() {
+    let cfg = get_contract_config(137);
+    let cid = condition_id_bytes32(CONDITION_ID).unwrap();
+    let (to, data) = redeem_call(RedeemRoute::Ctf, &cfg, cid, &[2], &[]).unwrap();
+
+    assert_eq!(format!("{:?}", to), cfg.conditional_tokens.to_lowercase());
+    assert_eq!(
+        hex::encode(&data),
+        concat!(
+            "01b7037c",
+            "0000000000000000000000002791bca1f2de4661ed88a30c99a7a9449aa84174",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "5f65177b394277fd294cd75650044e32ba009a95022d88a0c1d565897d72f8f1",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+        )
+    );
+}
+
+#[test]
+fn neg_risk_route_encodes_adapter_amounts_for_winning_outcomes_only() {
+    let cfg = get_contract_config(137);
+    let cid = condition_id_bytes32(CONDITION_ID).unwrap();
+    // Outcome 0 lost, outcome 1 (index set 2) won: only the winning balance is redeemed.
+    let balances = [U256::from(5_000_000u64), U256::from(12_340_000u64)];
+    let (to, data) = redeem_call(RedeemRoute::NegRiskAdapter, &cfg, cid, &[2], &balances).unwrap();
+
+    assert_eq!(format!("{:?}", to), cfg.neg_risk_adapter.to_lowercase());
+    assert_eq!(
+        hex::encode(&data),
+        concat!(
+            "dbeccb23",
+            "5f65177b394277fd294cd75650044e32ba009a95022d88a0c1d565897d72f8f1",
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000bc4b20",
+        )
+    );
+}
+
+#[test]
+fn neg_risk_route_requires_outcome_balances() {
+    let cfg = get_contract_config(137);
+    let cid = condition_id_bytes32(CONDITION_ID).unwrap();
+    assert!(redeem_call(RedeemRoute::NegRiskAdapter, &cfg, cid, &[1], &[]).is_err());
+}
+
+#[test]
+fn condition_id_is_left_padded_like_hex_zero_pad() {
+    let cid = condition_id_bytes32("0x1234").unwrap();
+    assert_eq!(&cid[..30], &[0u8; 30]);
+    assert_eq!(&cid[30..], &[0x12, 0x34]);
+    assert_eq!(condition_id_bytes32("4660").unwrap(), cid);
+}
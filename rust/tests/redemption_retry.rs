@@ -0,0 +1,31 @@
+use anyhow::{anyhow, Context};
+use ethers::providers::{HttpClientError, JsonRpcError, ProviderError};
+use polymarket_copy_bot::redemption::{is_retryable, RedeemUnconfirmed};
+
+fn rpc_error(code: i64, message: &str) -> anyhow::Error {
+    let err = HttpClientError::JsonRpcError(JsonRpcError { code, message: message.to_string(), data: None });
+    anyhow::Error::from(ProviderError::JsonRpcClientError(Box::new(err))).context("balanceOf call")
+}
+
+#[tokio::test]
+async fn only_unreachable_or_overloaded_rpcs_are_retried() {
+    let refused = reqwest::get("http://127.0.0.1:1").await.unwrap_err();
+    assert!(is_retryable(&anyhow::Error::from(ProviderError::HTTPError(refused)).context("getCollectionId call")));
+    assert!(is_retryable(&rpc_error(-32005, "limit exceeded")));
+    let gateway_page = HttpClientError::SerdeJson {
+        err: serde_json::from_str::<serde_json::Value>("<html>502 Bad Gateway</html>").unwrap_err(),
+        text: "<html>502 Bad Gateway</html>".to_string(),
+    };
+    assert!(is_retryable(&ProviderError::JsonRpcClientError(Box::new(gateway_page)).into()));
+
+    assert!(!is_retryable(&rpc_error(3, "execution reverted")));
+    // Messages are not inspected.
+    assert!(!is_retryable(&anyhow!("connection timed out: nonce too low")));
+    assert!(!is_retryable(&anyhow!("Market is not yet resolved: 0xabc")));
+
+    // A sent transaction whose receipt timed out is never sent again.
+    let unconfirmed = Err::<(), _>(rpc_error(-32005, "limit exceeded"))
+        .context(RedeemUnconfirmed { tx_hash: "0x01".to_string() })
+        .unwrap_err();
+    assert!(!is_retryable(&unconfirmed));
+}
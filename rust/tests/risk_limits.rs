@@ -0,0 +1,158 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use polymarket_copy_bot::config::RiskConfig;
+use polymarket_copy_bot::order::{resting_orders_path, save_resting_orders, OrderSide, RestingOrder};
+use polymarket_copy_bot::{
+    record_target_buy, record_target_sell, settle_target_market, target_ledger_path, RiskManager, RiskViolation,
+};
+use std::path::Path;
+
+const WHALE: &str = "0xaaaa000000000000000000000000000000000001";
+const MINNOW: &str = "0xbbbb000000000000000000000000000000000002";
+
+fn at(hour: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, 1, hour, 0, 0).unwrap()
+}
+
+fn manager(holdings: &Path, limits: RiskConfig) -> RiskManager {
+    RiskManager::new(limits, holdings)
+}
+
+#[test]
+fn exposure_limits_count_every_targets_positions() {
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let ledger = target_ledger_path(&holdings);
+    record_target_buy(&ledger, WHALE, "m1", "yes", 100.0, 40.0).unwrap();
+    record_target_buy(&ledger, MINNOW, "m1", "no", 50.0, 20.0).unwrap();
+    record_target_buy(&ledger, MINNOW, "m2", "yes", 10.0, 5.0).unwrap();
+
+    let risk = manager(
+        &holdings,
+        RiskConfig { max_market_exposure: Some(70.0), max_total_exposure: Some(80.0), ..Default::default() },
+    );
+    assert_eq!(risk.check_buy_at("m1", "yes", 10.0, at(12)).unwrap(), None);
+    assert_eq!(
+        risk.check_buy_at("m1", "yes", 15.0, at(12)).unwrap(),
+        Some(RiskViolation::MarketExposure { market: "m1".to_string(), current: 60.0, order: 15.0, limit: 70.0 })
+    );
+    assert_eq!(risk.check_buy_at("m2", "yes", 10.0, at(12)).unwrap(), None);
+    let total = risk.check_buy_at("m3", "yes", 20.0, at(12)).unwrap().unwrap();
+    assert_eq!(total.code(), "max_total_exposure");
+    assert!(total.to_string().starts_with("risk limit max_total_exposure: 65.00 + 20.00"), "{}", total);
+}
+
+#[test]
+fn open_position_limit_still_allows_adding_to_held_tokens() {
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let ledger = target_ledger_path(&holdings);
+    record_target_buy(&ledger, WHALE, "m1", "yes", 10.0, 5.0).unwrap();
+    record_target_buy(&ledger, MINNOW, "m1", "yes", 10.0, 5.0).unwrap();
+    record_target_buy(&ledger, MINNOW, "m2", "yes", 10.0, 5.0).unwrap();
+
+    let risk = manager(&holdings, RiskConfig { max_open_positions: Some(2), ..Default::default() });
+    assert_eq!(risk.check_buy_at("m1", "yes", 5.0, at(12)).unwrap(), None);
+    assert_eq!(
+        risk.check_buy_at("m3", "yes", 5.0, at(12)).unwrap(),
+        Some(RiskViolation::OpenPositions { open: 2, limit: 2 })
+    );
+}
+
+#[test]
+fn spend_is_limited_over_a_rolling_day() {
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let risk = manager(&holdings, RiskConfig { max_daily_spend: Some(100.0), ..Default::default() });
+
+    risk.record_buy_at(60.0, at(1)).unwrap();
+    risk.record_buy_at(30.0, at(20)).unwrap();
+    let rejected = risk.check_buy_at("m1", "yes", 20.0, at(23)).unwrap().unwrap();
+    assert_eq!(rejected.code(), "max_daily_spend");
+    // 24h after the first buy only the second one counts.
+    assert_eq!(risk.check_buy_at("m1", "yes", 20.0, at(1) + Duration::hours(24)).unwrap(), None);
+    assert!((risk.spent_last_24h(at(1) + Duration::hours(24)) - 30.0).abs() < 1e-9);
+}
+
+#[test]
+fn daily_loss_halts_buys_until_the_next_utc_day() {
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let ledger = target_ledger_path(&holdings);
+    // Losses booked before the day's first trade are part of its baseline.
+    record_target_buy(&ledger, WHALE, "m0", "yes", 100.0, 50.0).unwrap();
+    record_target_sell(&ledger, WHALE, "m0", "yes", 100.0, 10.0).unwrap();
+    let risk = manager(&holdings, RiskConfig { max_daily_loss: Some(25.0), ..Default::default() });
+    assert_eq!(risk.check_buy_at("m1", "yes", 5.0, at(8)).unwrap(), None);
+
+    record_target_buy(&ledger, WHALE, "m1", "yes", 100.0, 50.0).unwrap();
+    record_target_sell(&ledger, WHALE, "m1", "yes", 100.0, 20.0).unwrap();
+    let halted = risk.check_buy_at("m2", "yes", 5.0, at(9)).unwrap().unwrap();
+    assert_eq!(halted, RiskViolation::DailyLossLimit { realized: -30.0, limit: 25.0 });
+
+    assert_eq!(risk.check_buy_at("m2", "yes", 5.0, at(9) + Duration::days(1)).unwrap(), None);
+}
+
+#[test]
+fn redemption_losses_booked_after_rolling_the_day_count_towards_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let ledger = target_ledger_path(&holdings);
+    record_target_buy(&ledger, WHALE, "m1", "yes", 100.0, 40.0).unwrap();
+    let risk = manager(&holdings, RiskConfig { max_daily_loss: Some(25.0), ..Default::default() });
+    // Yesterday's baseline; the redemption is today's first event.
+    assert_eq!(risk.check_buy_at("m2", "yes", 5.0, Utc::now() - Duration::days(1)).unwrap(), None);
+
+    risk.roll_day().unwrap();
+    settle_target_market(&ledger, "m1", 0.0, &["no".to_string()]).unwrap();
+    let halted = risk.check_buy("m2", "yes", 5.0).unwrap().unwrap();
+    assert_eq!(halted, RiskViolation::DailyLossLimit { realized: -40.0, limit: 25.0 });
+}
+
+#[test]
+fn resting_buys_count_towards_exposure_and_spend() {
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let ledger = target_ledger_path(&holdings);
+    record_target_buy(&ledger, WHALE, "m1", "yes", 50.0, 20.0).unwrap();
+    let resting = |order_id: &str, side: OrderSide, size_matched: f64| RestingOrder {
+        order_id: order_id.to_string(),
+        target: WHALE.to_string(),
+        condition_id: "m1".to_string(),
+        token_id: "no".to_string(),
+        side,
+        price: 0.4,
+        size: 100.0,
+        size_matched,
+        placed_at: at(10).timestamp(),
+    };
+    // 50 unfilled shares at 0.4 are 20 USDC still to be spent; resting SELLs do not count.
+    save_resting_orders(
+        &resting_orders_path(&holdings),
+        &[resting("0x1", OrderSide::Buy, 50.0), resting("0x2", OrderSide::Sell, 0.0)],
+    )
+    .unwrap();
+
+    let risk = manager(
+        &holdings,
+        RiskConfig {
+            max_market_exposure: Some(50.0),
+            max_open_positions: Some(2),
+            max_daily_spend: Some(30.0),
+            ..Default::default()
+        },
+    );
+    let exposure = risk.exposure("m1", "no");
+    assert!((exposure.market - 40.0).abs() < 1e-9);
+    assert_eq!(exposure.open_positions, 2);
+    assert!(exposure.holds_token);
+    assert_eq!(
+        risk.check_buy_at("m1", "yes", 15.0, at(12)).unwrap(),
+        Some(RiskViolation::MarketExposure { market: "m1".to_string(), current: 40.0, order: 15.0, limit: 50.0 })
+    );
+    assert_eq!(risk.check_buy_at("m2", "yes", 5.0, at(12)).unwrap().unwrap().code(), "max_open_positions");
+    risk.record_buy_at(8.0, at(11)).unwrap();
+    assert_eq!(
+        risk.check_buy_at("m1", "no", 5.0, at(12)).unwrap(),
+        Some(RiskViolation::DailySpend { spent: 28.0, order: 5.0, limit: 30.0 })
+    );
+}
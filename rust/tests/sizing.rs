@@ -0,0 +1,69 @@
+use polymarket_copy_bot::order::{sizing_strategy, SizingInput};
+use polymarket_copy_bot::{Config, Sizing, TargetConfig};
+use std::collections::HashMap;
+
+const KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const TARGET: &str = "0x1111111111111111111111111111111111111111";
+
+fn resolve(toml: &str) -> anyhow::Result<Config> {
+    let env: HashMap<String, String> = HashMap::from([("PRIVATE_KEY".to_string(), KEY.to_string())]);
+    Config::resolve(toml, |k| env.get(k).cloned())
+}
+
+fn amount(sizing: Sizing, input: SizingInput) -> anyhow::Result<f64> {
+    sizing_strategy(&sizing).amount(&input)
+}
+
+#[test]
+fn strategies_size_from_their_inputs() {
+    let input = SizingInput {
+        target_amount: 50.0,
+        available_balance: Some(200.0),
+        our_equity: Some(1_000.0),
+        target_equity: Some(10_000.0),
+    };
+    assert_eq!(amount(Sizing::Multiplier { multiplier: 0.5 }, input).unwrap(), 25.0);
+    assert_eq!(amount(Sizing::FixedUsd { usd: 10.0 }, input).unwrap(), 10.0);
+    assert_eq!(amount(Sizing::BalancePercent { percent: 5.0 }, input).unwrap(), 10.0);
+    assert!((amount(Sizing::PortfolioProportional { multiplier: 2.0 }, input).unwrap() - 10.0).abs() < 1e-9);
+
+    let unknown = SizingInput { target_amount: 50.0, ..Default::default() };
+    assert!(amount(Sizing::BalancePercent { percent: 5.0 }, unknown).is_err());
+    assert!(amount(Sizing::PortfolioProportional { multiplier: 1.0 }, unknown).is_err());
+    assert!(!sizing_strategy(&Sizing::FixedUsd { usd: 1.0 }).needs_balance());
+}
+
+#[test]
+fn size_multiplier_is_the_default_strategy() {
+    let target = TargetConfig { size_multiplier: 0.25, ..TargetConfig::new(TARGET) };
+    assert_eq!(target.sizing(), Sizing::Multiplier { multiplier: 0.25 });
+}
+
+#[test]
+fn sizing_is_read_per_target_and_validated() {
+    let config = resolve(&format!(
+        r#"
+        [[targets]]
+        wallet = "{TARGET}"
+        sizing = {{ strategy = "fixed_usd", usd = 10 }}
+        [[targets]]
+        wallet = "0x2222222222222222222222222222222222222222"
+        sizing = {{ strategy = "portfolio_proportional" }}
+        "#
+    ))
+    .unwrap();
+    assert_eq!(config.targets[0].sizing(), Sizing::FixedUsd { usd: 10.0 });
+    assert_eq!(config.targets[1].sizing(), Sizing::PortfolioProportional { multiplier: 1.0 });
+    assert!(config.to_redacted_toml().unwrap().contains("strategy = \"fixed_usd\""));
+
+    let err = resolve(&format!(
+        r#"
+        [[targets]]
+        wallet = "{TARGET}"
+        sizing = {{ strategy = "balance_percent", percent = 150 }}
+        "#
+    ))
+    .unwrap_err();
+    assert!(err.to_string().contains("targets[0].sizing.percent"), "{}", err);
+    assert!(resolve(&format!("[[targets]]\nwallet = \"{TARGET}\"\nsizing = {{ strategy = \"kelly\" }}\n")).is_err());
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::book;
+use polymarket_copy_bot::clob::OrderBook;
+use polymarket_copy_bot::config::SlippageConfig;
+use polymarket_copy_bot::order::{check_book, worst_price, OrderSide};
+
+#[test]
+fn worst_price_applies_the_allowance_away_from_the_target() {
+    let none = SlippageConfig::default();
+    let cents = SlippageConfig { max_cents: Some(2.0), ..Default::default() };
+    let percent = SlippageConfig { max_percent: Some(10.0), ..Default::default() };
+
+    assert_eq!(worst_price(OrderSide::Buy, 0.53, &none, 0.01), 0.53);
+    assert_eq!(worst_price(OrderSide::Sell, 0.53, &none, 0.01), 0.53);
+    assert_eq!(worst_price(OrderSide::Buy, 0.53, &cents, 0.01), 0.55);
+    assert_eq!(worst_price(OrderSide::Sell, 0.53, &cents, 0.01), 0.51);
+    // 10% of 0.53: buy rounds down to 0.58, sell rounds up to 0.48.
+    assert_eq!(worst_price(OrderSide::Buy, 0.53, &percent, 0.01), 0.58);
+    assert_eq!(worst_price(OrderSide::Sell, 0.53, &percent, 0.01), 0.48);
+    // Kept one tick inside the price range.
+    assert_eq!(worst_price(OrderSide::Buy, 0.985, &cents, 0.01), 0.99);
+    assert_eq!(worst_price(OrderSide::Sell, 0.015, &cents, 0.01), 0.01);
+}
+
+#[test]
+fn book_check_explains_why_a_copy_cannot_fill() {
+    assert_eq!(
+        check_book(&book(), OrderSide::Sell, 10.0, 0.50, "FAK"),
+        Err("best bid 0.48 is past the worst allowed price 0.5".to_string())
+    );
+    assert_eq!(
+        check_book(&OrderBook::default(), OrderSide::Buy, 10.0, 0.5, "FAK"),
+        Err("no asks on the book".to_string())
+    );
+
+    // 30 shares are bid at 0.45 or better: FAK fills them, FOK is skipped.
+    let fak = check_book(&book(), OrderSide::Sell, 50.0, 0.45, "FAK").unwrap();
+    assert!(!fak.complete);
+    assert!((fak.shares - 30.0).abs() < 1e-9);
+    let fok = check_book(&book(), OrderSide::Sell, 50.0, 0.45, "FOK").unwrap_err();
+    assert_eq!(fok, "FOK for 50 shares but only 30.0000 fills at 0.45 or better");
+
+    let full = check_book(&book(), OrderSide::Buy, 5.2, 0.52, "FOK").unwrap();
+    assert!(full.complete);
+}
+
+#[test]
+fn slippage_is_cents_or_percent() {
+    let env = |k: &str| match k {
+        "PRIVATE_KEY" => Some("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string()),
+        "TARGET_WALLET" => Some("0x1111111111111111111111111111111111111111".to_string()),
+        "TARGETS_PATH" => Some("/nonexistent/targets.json".to_string()),
+        "MAX_SLIPPAGE_PERCENT" => Some("5".to_string()),
+        _ => None,
+    };
+    let config = polymarket_copy_bot::Config::resolve("", env).unwrap();
+    assert_eq!(config.slippage.max_percent, Some(5.0));
+
+    let err = polymarket_copy_bot::Config::resolve("[slippage]\nmax_cents = 2.0\n", env).unwrap_err();
+    assert!(err.to_string().contains("slippage: set max_cents or max_percent, not both"), "{}", err);
+}
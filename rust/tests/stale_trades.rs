@@ -0,0 +1,105 @@
+mod common;
+
+use common::{order_builder, serve_json};
+use polymarket_copy_bot::config::StaleConfig;
+use polymarket_copy_bot::feed::{timestamp_millis, timestamp_secs};
+use polymarket_copy_bot::order::{paper_account_path, PaperAccount, PaperFillModel};
+use polymarket_copy_bot::{trade_latency, FeedStats, StaleTradeGuard, TargetConfig, TradePayload};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const EXECUTED: u64 = 1_767_225_600;
+
+fn trade(side: &str, price: f64, timestamp: u64) -> TradePayload {
+    serde_json::from_value(serde_json::json!({
+        "proxyWallet": "0x1111111111111111111111111111111111111111",
+        "conditionId": "0xm1",
+        "asset": "1",
+        "side": side,
+        "price": price,
+        "size": 10.0,
+        "timestamp": timestamp
+    }))
+    .unwrap()
+}
+
+fn at(secs: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+#[test]
+fn latency_is_measured_from_second_or_millisecond_timestamps() {
+    let now = at(EXECUTED + 4);
+    assert_eq!(trade_latency(&trade("BUY", 0.5, EXECUTED), now), Some(Duration::from_secs(4)));
+    assert_eq!(trade_latency(&trade("BUY", 0.5, EXECUTED * 1000 + 1500), now), Some(Duration::from_millis(2500)));
+    assert_eq!(timestamp_millis(EXECUTED), EXECUTED * 1000);
+    assert_eq!(timestamp_millis(EXECUTED * 1000 + 1500), EXECUTED * 1000 + 1500);
+    assert_eq!(timestamp_secs(EXECUTED * 1000 + 1500), EXECUTED + 1);
+
+    let stats = FeedStats::default();
+    stats.record_latency(Duration::from_millis(1000));
+    stats.record_latency(Duration::from_millis(3000));
+    assert_eq!(stats.trades(), 2);
+    assert_eq!(stats.avg_latency(), Duration::from_millis(2000));
+    assert_eq!(stats.max_latency(), Duration::from_millis(3000));
+    assert_eq!(stats.last_latency(), Duration::from_millis(3000));
+}
+
+#[tokio::test]
+async fn late_buys_are_skipped_without_a_drift_allowance() {
+    let guard = StaleTradeGuard::new(
+        StaleConfig { max_age_secs: Some(30), ..Default::default() },
+        "http://127.0.0.1:1".to_string(),
+    );
+    let late = at(EXECUTED + 95);
+    assert_eq!(guard.check_at(&trade("BUY", 0.55, EXECUTED), at(EXECUTED + 10)).await, Ok(()));
+    assert_eq!(
+        guard.check_at(&trade("BUY", 0.55, EXECUTED), late).await,
+        Err("detected 95s after execution, over 30s".to_string())
+    );
+    assert_eq!(guard.check_at(&trade("SELL", 0.55, EXECUTED), late).await, Ok(()));
+
+    let disabled = StaleTradeGuard::new(StaleConfig::default(), "http://127.0.0.1:1".to_string());
+    assert_eq!(disabled.check_at(&trade("BUY", 0.55, EXECUTED), late).await, Ok(()));
+}
+
+#[tokio::test]
+async fn late_buys_are_copied_while_the_midpoint_stays_close() {
+    let url = serve_json(serde_json::json!({ "mid": "0.58" })).await;
+    let config = |drift| StaleConfig { max_age_secs: Some(30), max_midpoint_drift: Some(drift) };
+    let late = at(EXECUTED + 95);
+
+    let loose = StaleTradeGuard::new(config(0.05), url.clone());
+    assert_eq!(loose.check_at(&trade("BUY", 0.55, EXECUTED), late).await, Ok(()));
+
+    let tight = StaleTradeGuard::new(config(0.02), url);
+    assert_eq!(
+        tight.check_at(&trade("BUY", 0.55, EXECUTED), late).await,
+        Err("detected 95s after execution and midpoint 0.58 is more than 0.02 from the target's price 0.55".to_string())
+    );
+    assert_eq!(tight.skipped(), 0);
+}
+
+#[tokio::test]
+async fn stale_buys_still_count_towards_the_targets_position() {
+    let dir = tempfile::tempdir().unwrap();
+    let holdings = dir.path().join("token-holding.json");
+    let builder = order_builder(String::new(), &holdings, "FAK").with_paper_trading(1000.0, PaperFillModel::TradePrice);
+    let guard = StaleTradeGuard::new(
+        StaleConfig { max_age_secs: Some(30), ..Default::default() },
+        "http://127.0.0.1:1".to_string(),
+    );
+    let target = TargetConfig::new("0x1111111111111111111111111111111111111111");
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+    // As the feed handler does: record every trade, copy only those the guard lets through.
+    // The stale BUY is skipped, but the target then holds 20, so its SELL of 10 is half.
+    for trade in [trade("BUY", 0.5, now - 600), trade("BUY", 0.5, now), trade("SELL", 0.5, now)] {
+        let sold_fraction = builder.record_target_trade(&trade, &target).await;
+        if guard.allows(&trade, target.name()).await {
+            assert!(builder.copy_recorded_trade(&trade, &target, sold_fraction).await.unwrap().success);
+        }
+    }
+    assert_eq!(guard.skipped(), 1);
+    let account = PaperAccount::load(&paper_account_path(&holdings), 1000.0);
+    assert!((account.positions["1"].shares - 5.0).abs() < 1e-9);
+}
@@ -0,0 +1,107 @@
+use polymarket_copy_bot::{
+    get_target_holdings, get_target_ledgers, record_target_buy, record_target_sell, settle_target_market,
+    MarketFilter, TargetConfig, TradePayload,
+};
+use std::path::PathBuf;
+
+const WHALE: &str = "0xAAAA000000000000000000000000000000000001";
+const MINNOW: &str = "0xbbbb000000000000000000000000000000000002";
+
+fn ledger_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("target-holdings-{}-{}.json", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn trade(slug: &str, title: &str) -> TradePayload {
+    serde_json::from_value(serde_json::json!({
+        "asset": "1",
+        "price": 0.5,
+        "side": "BUY",
+        "size": 10.0,
+        "slug": slug,
+        "title": title
+    }))
+    .unwrap()
+}
+
+#[test]
+fn sells_only_close_the_selling_targets_position() {
+    let path = ledger_path("sell");
+    record_target_buy(&path, WHALE, "m1", "t1", 20.0, 10.0).unwrap();
+    record_target_buy(&path, MINNOW, "m1", "t1", 10.0, 4.0).unwrap();
+
+    let pnl = record_target_sell(&path, &WHALE.to_lowercase(), "m1", "t1", 10.0, 7.0).unwrap();
+    assert!((pnl - 2.0).abs() < 1e-9);
+    assert_eq!(get_target_holdings(&path, WHALE, "m1", "t1"), 10.0);
+    assert_eq!(get_target_holdings(&path, MINNOW, "m1", "t1"), 10.0);
+
+    let ledgers = get_target_ledgers(&path);
+    assert!((ledgers[&WHALE.to_lowercase()].realized_pnl - 2.0).abs() < 1e-9);
+    assert_eq!(ledgers[&MINNOW.to_lowercase()].realized_pnl, 0.0);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn redemption_payout_is_split_by_shares() {
+    let path = ledger_path("settle");
+    record_target_buy(&path, WHALE, "m1", "t1", 30.0, 15.0).unwrap();
+    record_target_buy(&path, MINNOW, "m1", "t1", 10.0, 6.0).unwrap();
+    record_target_buy(&path, MINNOW, "m2", "t2", 5.0, 2.0).unwrap();
+
+    settle_target_market(&path, "m1", 40.0, &["t1".to_string()]).unwrap();
+
+    let ledgers = get_target_ledgers(&path);
+    let whale = &ledgers[&WHALE.to_lowercase()];
+    let minnow = &ledgers[&MINNOW.to_lowercase()];
+    assert!((whale.realized_pnl - 15.0).abs() < 1e-9);
+    assert!((minnow.realized_pnl - 4.0).abs() < 1e-9);
+    assert!(whale.positions.is_empty());
+    assert_eq!(get_target_holdings(&path, MINNOW, "m2", "t2"), 5.0);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn redemption_payout_goes_to_winning_token_holders_only() {
+    let path = ledger_path("settle-mixed");
+    // The whale holds the winning YES, the minnow the losing NO.
+    record_target_buy(&path, WHALE, "m1", "yes", 30.0, 15.0).unwrap();
+    record_target_buy(&path, MINNOW, "m1", "no", 10.0, 6.0).unwrap();
+    record_target_buy(&path, MINNOW, "m1", "yes", 10.0, 5.0).unwrap();
+
+    settle_target_market(&path, "m1", 40.0, &["yes".to_string()]).unwrap();
+
+    let ledgers = get_target_ledgers(&path);
+    // Whale: 30/40 of the payout less 15 cost; minnow: 10/40 less 5 + 6 cost.
+    assert!((ledgers[&WHALE.to_lowercase()].realized_pnl - 15.0).abs() < 1e-9);
+    assert!((ledgers[&MINNOW.to_lowercase()].realized_pnl - -1.0).abs() < 1e-9);
+    assert!(ledgers.values().all(|l| l.positions.is_empty()));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn market_filter_matches_slug_and_title_keywords() {
+    let filter = MarketFilter {
+        include: vec!["NBA".to_string()],
+        exclude: vec!["preseason".to_string()],
+        ..Default::default()
+    };
+    assert!(filter.allows(&trade("nba-lakers-celtics", "Lakers vs. Celtics")));
+    assert!(!filter.allows(&trade("nba-preseason-game", "Preseason")));
+    assert!(!filter.allows(&trade("btc-above-100k", "Bitcoin above 100k?")));
+    assert!(MarketFilter::default().allows(&trade("anything", "")));
+}
+
+#[test]
+fn target_config_defaults_from_json() {
+    let targets: Vec<TargetConfig> = serde_json::from_str(
+        r#"[{ "wallet": "0xAbC", "label": "whale", "max_order_amount": 50 }, { "wallet": "0xdef", "enabled": false }]"#,
+    )
+    .unwrap();
+    assert_eq!(targets[0].name(), "whale");
+    assert_eq!(targets[0].size_multiplier, 1.0);
+    assert_eq!(targets[0].max_order_amount, Some(50.0));
+    assert!(targets[0].enabled && targets[0].is_wallet("0xabc"));
+    assert_eq!(targets[1].name(), "0xdef");
+    assert!(!targets[1].enabled);
+}